@@ -5,7 +5,7 @@ use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{DefaultTerminal, Frame, layout::Rect, widgets::Block};
 
 use rand::{Rng, rng};
-use rataudio_rta::{Band, RTA};
+use rataudio_rta::{Band, RTA, RTAState};
 
 use simplelog::*;
 use std::fs::File;
@@ -39,6 +39,8 @@ fn run(mut terminal: DefaultTerminal) -> Result<()> {
     let f_min: f64 = 20.0;
     let f_max: f64 = 20000.0;
     let n_bands = 30;
+    let mut rta_state = RTAState::new();
+    let mut show_band_maxima = false;
 
     // Generate frequency bands logarithmically spaced between f_min and f_max
     // Produce a vector of `Band` instances
@@ -60,25 +62,30 @@ fn run(mut terminal: DefaultTerminal) -> Result<()> {
             }
         }
 
-        terminal.draw(|frame| draw(frame, &bands))?;
-        if handle_input()? == Command::Quit {
-            break Ok(());
+        terminal.draw(|frame| draw(frame, &bands, show_band_maxima, &mut rta_state))?;
+        match handle_input()? {
+            Command::Quit => break Ok(()),
+            Command::ToggleBandMaxima => show_band_maxima = !show_band_maxima,
+            Command::Noop => {}
         }
     }
 }
 
-fn draw(frame: &mut Frame, bands: &[Band]) {
+fn draw(frame: &mut Frame, bands: &[Band], show_band_maxima: bool, rta_state: &mut RTAState) {
     let rta_area = Rect::new(0, 0, 97, 24);
     let rta = RTA::new(bands.to_vec(), MIN_DB)
         .highlight_peak_band()
+        .alert_threshold_db(-1.0)
+        .show_band_maxima(show_band_maxima)
         .block(Block::bordered());
-    frame.render_widget(rta, rta_area);
+    frame.render_stateful_widget(rta, rta_area, rta_state);
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Command {
     Noop,
     Quit,
+    ToggleBandMaxima,
 }
 
 fn handle_input() -> Result<Command> {
@@ -88,6 +95,7 @@ fn handle_input() -> Result<Command> {
     match event::read()? {
         Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
             KeyCode::Char('q') => Ok(Command::Quit),
+            KeyCode::Char('m') => Ok(Command::ToggleBandMaxima),
             _ => Ok(Command::Noop),
         },
         _ => Ok(Command::Noop),
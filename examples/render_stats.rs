@@ -0,0 +1,47 @@
+//! Measures the render time of the RTA with `RenderStats`, with and without the chrome cache.
+//!
+//! Run in a release build: `cargo run --release --example render_stats`
+
+use std::time::Duration;
+
+use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+use rataudio_rta::{Band, RTA, RTAState, layout::ISO_THIRD_OCTAVE};
+
+const FRAMES: u32 = 2000;
+
+fn rta() -> RTA<'static> {
+    let bands = ISO_THIRD_OCTAVE
+        .iter()
+        .enumerate()
+        .map(|(i, &frequency)| Band::new((i % 10) as f32 / 10.0, frequency))
+        .collect();
+    RTA::new(bands, -60.0)
+}
+
+/// Renders `FRAMES` frames and returns the average render time. With `cached`, the state is kept
+/// between frames so the chrome is reused; otherwise every frame starts from a fresh state.
+fn average_render_time(area: Rect, cached: bool) -> Duration {
+    let mut state = RTAState::new();
+    let mut total = Duration::ZERO;
+    for frame in 0..FRAMES {
+        if !cached {
+            state = RTAState::new();
+        }
+        state.collect_render_stats(true);
+        let mut buf = Buffer::empty(area);
+        rta().render(area, &mut buf, &mut state);
+        let stats = state.render_stats().unwrap();
+        assert_eq!(stats.chrome_cached, cached && frame > 0);
+        total += stats.duration;
+    }
+    total / FRAMES
+}
+
+fn main() {
+    for (width, height) in [(80, 24), (200, 60)] {
+        let area = Rect::new(0, 0, width, height);
+        let uncached = average_render_time(area, false);
+        let cached = average_render_time(area, true);
+        println!("{width}x{height}: {uncached:?} uncached, {cached:?} cached");
+    }
+}
@@ -0,0 +1,120 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{Block, StatefulWidget, Widget},
+};
+use rataudio_rta::{
+    BarStyle, Band, DisplayScale, ErrorBarStyle, FrequencyAxis, PartialRounding, RTA, RTAState,
+};
+
+#[derive(Debug, Arbitrary)]
+struct BandInput {
+    value: f32,
+    frequency: Option<u16>,
+    deviation_db: Option<f32>,
+    channel_energies: Vec<f32>,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    x: u8,
+    y: u8,
+    width: u8,
+    height: u8,
+    min_db: f32,
+    bands: Vec<BandInput>,
+    bordered: bool,
+    show_peak_labels: bool,
+    highlight_peak_band: bool,
+    flag_invalid_bands: bool,
+    percent: bool,
+    note_axis: bool,
+    piano_keyboard: bool,
+    outline: bool,
+    stacked: bool,
+    whiskers: Option<bool>,
+    rounding: u8,
+    min_visible: bool,
+    reference_level_db: Option<f32>,
+    stateful: bool,
+    select: Option<(u16, u16)>,
+}
+
+fuzz_target!(|input: Input| {
+    let bands = input
+        .bands
+        .into_iter()
+        .map(|band| {
+            let mut b = Band::new(band.value, band.frequency.unwrap_or(0));
+            b.frequency = band.frequency;
+            b.deviation_db = band.deviation_db;
+            b.channel_energies = band.channel_energies;
+            b
+        })
+        .collect();
+
+    let mut rta = RTA::new(bands, input.min_db)
+        .show_peak_labels(input.show_peak_labels)
+        .flag_invalid_bands(input.flag_invalid_bands)
+        .piano_keyboard(input.piano_keyboard)
+        .min_visible(input.min_visible)
+        .partial_rounding(match input.rounding % 4 {
+            0 => PartialRounding::Floor,
+            1 => PartialRounding::Nearest,
+            2 => PartialRounding::Ceil,
+            _ => PartialRounding::Dithered,
+        });
+    if input.bordered {
+        rta = rta.block(Block::bordered());
+    }
+    if input.highlight_peak_band {
+        rta = rta.highlight_peak_band();
+    }
+    if input.percent {
+        rta = rta.display_scale(DisplayScale::Percent);
+    }
+    if input.note_axis {
+        rta = rta.frequency_axis(FrequencyAxis::Note);
+    }
+    if input.outline {
+        rta = rta.bar_style(BarStyle::Outline);
+    }
+    if input.stacked {
+        rta = rta.stacked_channels([Color::Red, Color::Blue]);
+    }
+    if let Some(shade) = input.whiskers {
+        rta = rta.error_bars(if shade {
+            ErrorBarStyle::Shade
+        } else {
+            ErrorBarStyle::Whisker
+        });
+    }
+    if let Some(db) = input.reference_level_db {
+        rta = rta.reference_level_db(db);
+    }
+
+    let area = Rect::new(
+        input.x.into(),
+        input.y.into(),
+        input.width.into(),
+        input.height.into(),
+    );
+    let mut buf = Buffer::empty(Rect::new(0, 0, 512, 512));
+    if input.stateful {
+        let mut state = RTAState::new();
+        if let Some((column, row)) = input.select {
+            state.select_at(column, row);
+        }
+        StatefulWidget::render(rta, area, &mut buf, &mut state);
+        if let Some((column, row)) = input.select {
+            state.select_at(column, row);
+        }
+    } else {
+        Widget::render(rta, area, &mut buf);
+    }
+});
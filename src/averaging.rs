@@ -0,0 +1,138 @@
+use crate::rta::Band;
+
+/// Averages successive band values and tracks their per-band standard deviation.
+///
+/// Values are averaged in dB. The deviation is stored in [`Band::deviation_db`] so it can be shown
+/// with [`crate::RTA::error_bars`]. Invalid and NaN values are left out of the average of their
+/// band, like they show as empty on the meter.
+#[derive(Debug, Clone)]
+pub struct BandAverager {
+    /// The lowest value of the dB scale the bands are measured on.
+    min_db: f32,
+    /// The sum of the levels of each band in dB.
+    sums: Vec<f64>,
+    /// The sum of the squared levels of each band in dB.
+    sums_of_squares: Vec<f64>,
+    /// The number of averaged values of each band.
+    counts: Vec<usize>,
+    /// The number of averaged snapshots.
+    count: usize,
+}
+
+impl BandAverager {
+    /// Creates a new `BandAverager` for bands on a scale starting at `min_db`.
+    pub fn new(min_db: f32) -> Self {
+        BandAverager {
+            min_db,
+            sums: Vec::new(),
+            sums_of_squares: Vec::new(),
+            counts: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Adds a snapshot of the band values to the average.
+    ///
+    /// The average starts over if the number of bands changed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn push(&mut self, bands: &[Band]) {
+        if bands.len() != self.sums.len() {
+            self.sums = vec![0.0; bands.len()];
+            self.sums_of_squares = vec![0.0; bands.len()];
+            self.counts = vec![0; bands.len()];
+            self.count = 0;
+        }
+        for (i, band) in bands.iter().enumerate() {
+            let db = band.get_db(self.min_db) as f64;
+            if band.invalid || !db.is_finite() {
+                continue;
+            }
+            self.sums[i] += db;
+            self.sums_of_squares[i] += db * db;
+            self.counts[i] += 1;
+        }
+        self.count += 1;
+    }
+
+    /// The number of snapshots in the average.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Discards all snapshots.
+    pub fn reset(&mut self) {
+        self.sums.clear();
+        self.sums_of_squares.clear();
+        self.counts.clear();
+        self.count = 0;
+    }
+
+    /// Sets the value of each band to its average and its deviation to the standard deviation.
+    ///
+    /// Bands are left untouched if nothing has been averaged yet or the number of bands differs,
+    /// and bands that only had invalid values so far are left untouched too.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn apply(&self, bands: &mut [Band]) {
+        if self.count == 0 || bands.len() != self.sums.len() {
+            return;
+        }
+        for (i, band) in bands.iter_mut().enumerate() {
+            let (sum, sum_of_squares) = (self.sums[i], self.sums_of_squares[i]);
+            let n = self.counts[i] as f64;
+            if n == 0.0 {
+                continue;
+            }
+            let mean = sum / n;
+            let variance = (sum_of_squares / n - mean * mean).max(0.0);
+            band.set_db(mean as f32, self.min_db);
+            band.deviation_db = Some(variance.sqrt() as f32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN_DB: f32 = -60.0;
+
+    fn bands(levels_db: &[f32]) -> Vec<Band> {
+        levels_db
+            .iter()
+            .map(|&db| {
+                let mut band = Band::new(0.0, 1000);
+                band.set_db(db, MIN_DB);
+                band
+            })
+            .collect()
+    }
+
+    #[test]
+    fn averages_in_db_with_deviation() {
+        let mut averager = BandAverager::new(MIN_DB);
+        averager.push(&bands(&[-10.0, -40.0]));
+        averager.push(&bands(&[-20.0, -40.0]));
+        let mut result = bands(&[0.0, 0.0]);
+        averager.apply(&mut result);
+        assert!((result[0].get_db(MIN_DB) + 15.0).abs() < 1e-3);
+        assert!((result[0].deviation_db.unwrap() - 5.0).abs() < 1e-3);
+        assert!((result[1].get_db(MIN_DB) + 40.0).abs() < 1e-3);
+        assert!(result[1].deviation_db.unwrap() < 1e-2);
+    }
+
+    #[test]
+    fn nan_values_are_skipped() {
+        let mut averager = BandAverager::new(MIN_DB);
+        averager.push(&bands(&[-10.0, f32::NAN]));
+        let mut nan = bands(&[-30.0, -30.0]);
+        nan[0].value = f32::NAN;
+        averager.push(&nan);
+        averager.push(&bands(&[-20.0, -30.0]));
+        assert_eq!(averager.count(), 3);
+
+        let mut result = bands(&[0.0, 0.0]);
+        averager.apply(&mut result);
+        assert!((result[0].get_db(MIN_DB) + 15.0).abs() < 1e-3);
+        assert!((result[1].get_db(MIN_DB) + 30.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,215 @@
+use ratatui::{
+    prelude::{Buffer, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, StatefulWidget},
+};
+
+#[cfg(feature = "config")]
+use crate::config::{BandConfig, RtaConfig};
+use crate::{
+    music::{frequency_to_midi, note_name},
+    rta::Band,
+};
+
+/// The frequency the first band added to an empty layout starts at.
+const DEFAULT_FREQUENCY: u16 = 1000;
+
+/// The band layout edited with a [`BandEditor`]: a sorted list of center frequencies with one
+/// selected.
+///
+/// The application maps keys to the editing methods, e.g. up and down to
+/// [`BandEditorState::select_previous`] and [`BandEditorState::select_next`], and builds the
+/// meter from [`BandEditorState::bands`] once done.
+///
+/// ```
+/// use rataudio_rta::BandEditorState;
+///
+/// let mut editor = BandEditorState::new([250, 1000]);
+/// editor.add();
+/// assert_eq!(editor.frequencies(), [250, 500, 1000]);
+/// editor.shift_selected(12.0);
+/// assert_eq!(editor.frequencies(), [250, 999, 1000]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BandEditorState {
+    /// The center frequencies of the bands in Hz, sorted and without duplicates.
+    frequencies: Vec<u16>,
+    /// The index of the selected band, if there are any bands.
+    selected: Option<usize>,
+}
+
+impl BandEditorState {
+    /// Creates a new state editing a layout with the given center frequencies in Hz, selecting
+    /// the first band.
+    ///
+    /// The frequencies are sorted and duplicates and 0 Hz are dropped.
+    pub fn new(frequencies: impl Into<Vec<u16>>) -> Self {
+        let mut frequencies = frequencies.into();
+        frequencies.retain(|&frequency| frequency > 0);
+        frequencies.sort_unstable();
+        frequencies.dedup();
+        let selected = (!frequencies.is_empty()).then_some(0);
+        BandEditorState {
+            frequencies,
+            selected,
+        }
+    }
+
+    /// Creates a new state editing the layout of `bands`. Bands without a frequency are dropped.
+    pub fn from_bands(bands: &[Band]) -> Self {
+        Self::new(
+            bands
+                .iter()
+                .filter_map(|band| band.frequency)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// The center frequencies of the bands in Hz, in ascending order.
+    pub fn frequencies(&self) -> &[u16] {
+        &self.frequencies
+    }
+
+    /// The index of the selected band, if there are any bands.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects the next higher band, if any.
+    pub fn select_next(&mut self) {
+        if let Some(selected) = self.selected {
+            self.selected = Some((selected + 1).min(self.frequencies.len() - 1));
+        }
+    }
+
+    /// Selects the next lower band, if any.
+    pub fn select_previous(&mut self) {
+        if let Some(selected) = self.selected {
+            self.selected = Some(selected.saturating_sub(1));
+        }
+    }
+
+    /// Adds a band halfway between the selected band and the next higher one on a logarithmic
+    /// scale, or an octave above the selected band if it is the highest, and selects it.
+    ///
+    /// Nothing is added if there is no free frequency in between.
+    pub fn add(&mut self) {
+        let Some(selected) = self.selected else {
+            self.frequencies.push(DEFAULT_FREQUENCY);
+            self.selected = Some(0);
+            return;
+        };
+        let low = self.frequencies[selected] as f32;
+        let frequency = match self.frequencies.get(selected + 1) {
+            Some(&high) => (low * high as f32).sqrt(),
+            None => low * 2.0,
+        }
+        .round()
+        .min(u16::MAX as f32) as u16;
+        if let Err(index) = self.frequencies.binary_search(&frequency) {
+            self.frequencies.insert(index, frequency);
+            self.selected = Some(index);
+        }
+    }
+
+    /// Removes the selected band and selects the band that took its place, or the new highest
+    /// band.
+    pub fn remove(&mut self) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        self.frequencies.remove(selected);
+        self.selected = match self.frequencies.len() {
+            0 => None,
+            len => Some(selected.min(len - 1)),
+        };
+    }
+
+    /// Moves the selected band by `semitones`, which may be fractional or negative. The band
+    /// stops short of its neighbors so the order of the bands is kept.
+    pub fn shift_selected(&mut self, semitones: f32) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let lowest = match selected {
+            0 => 1,
+            _ => self.frequencies[selected - 1].saturating_add(1),
+        };
+        let highest = self
+            .frequencies
+            .get(selected + 1)
+            .map_or(u16::MAX, |&frequency| frequency - 1);
+        let frequency = self.frequencies[selected] as f32 * 2_f32.powf(semitones / 12.0);
+        self.frequencies[selected] = (frequency.round().clamp(0.0, u16::MAX as f32) as u16)
+            .clamp(lowest.min(highest), highest);
+    }
+
+    /// Creates bands for the layout, all starting at zero.
+    pub fn bands(&self) -> Vec<Band> {
+        self.frequencies
+            .iter()
+            .map(|&frequency| Band::new(0.0, frequency))
+            .collect()
+    }
+
+    /// Creates a configuration with the layout and default settings, e.g. to save it as a preset
+    /// with [`RtaConfig::save`].
+    #[cfg(feature = "config")]
+    pub fn to_config(&self) -> RtaConfig {
+        RtaConfig {
+            bands: self
+                .frequencies
+                .iter()
+                .map(|&frequency| BandConfig {
+                    frequency,
+                    color: None,
+                })
+                .collect(),
+            ..RtaConfig::default()
+        }
+    }
+}
+
+/// A widget listing the bands of a [`BandEditorState`] with their center frequency and nearest
+/// note, highlighting the selected band.
+#[derive(Debug, Clone, Default)]
+pub struct BandEditor<'a> {
+    /// The block that surrounds the list, if any.
+    block: Option<Block<'a>>,
+}
+
+impl<'a> BandEditor<'a> {
+    /// Creates a new `BandEditor` widget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Surrounds the `BandEditor` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl StatefulWidget for BandEditor<'_> {
+    type State = BandEditorState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let items = state.frequencies.iter().map(|&frequency| {
+            let note = note_name(frequency_to_midi(frequency as f32).round() as i32);
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{frequency:>5} Hz ")),
+                Span::raw(note).dim(),
+            ]))
+        });
+
+        let mut list = List::new(items).highlight_style(Style::new().reversed());
+        if let Some(block) = self.block {
+            list = list.block(block);
+        }
+        let mut list_state = ListState::default().with_selected(state.selected);
+        StatefulWidget::render(list, area, buf, &mut list_state);
+    }
+}
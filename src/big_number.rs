@@ -0,0 +1,152 @@
+use ratatui::{
+    layout::Alignment,
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::Style,
+    widgets::{Block, Paragraph},
+};
+
+/// The number of rows of a big glyph.
+const GLYPH_HEIGHT: u16 = 5;
+
+/// Returns the rows of the big glyph for `c`, or `None` if it has no big glyph.
+fn glyph(c: char) -> Option<[&'static str; GLYPH_HEIGHT as usize]> {
+    let rows = match c {
+        '0' => ["███", "█ █", "█ █", "█ █", "███"],
+        '1' => ["██ ", " █ ", " █ ", " █ ", "███"],
+        '2' => ["███", "  █", "███", "█  ", "███"],
+        '3' => ["███", "  █", "███", "  █", "███"],
+        '4' => ["█ █", "█ █", "███", "  █", "  █"],
+        '5' => ["███", "█  ", "███", "  █", "███"],
+        '6' => ["███", "█  ", "███", "█ █", "███"],
+        '7' => ["███", "  █", "  █", "  █", "  █"],
+        '8' => ["███", "█ █", "███", "█ █", "███"],
+        '9' => ["███", "█ █", "███", "  █", "███"],
+        '-' => ["   ", "   ", "███", "   ", "   "],
+        '+' => ["   ", " █ ", "███", " █ ", "   "],
+        '.' => [" ", " ", " ", " ", "█"],
+        ':' => [" ", "█", " ", "█", " "],
+        ' ' => ["  ", "  ", "  ", "  ", "  "],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+/// A widget displaying a number in large block digits, readable from across a room.
+///
+/// Digits, signs, `.` and `:` are drawn five rows high. If the area is too small for the big
+/// digits, the text is rendered normally instead.
+#[derive(Debug, Clone)]
+pub struct BigNumber<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The text to display.
+    text: String,
+    /// A unit shown in normal text after the digits, if any.
+    unit: Option<String>,
+    /// The style of the digits.
+    style: Style,
+    /// The horizontal alignment of the digits.
+    alignment: Alignment,
+}
+
+impl<'a> BigNumber<'a> {
+    /// Creates a new `BigNumber` displaying `text`, e.g. a formatted level such as `"-23.0"`.
+    pub fn new(text: impl Into<String>) -> Self {
+        BigNumber {
+            block: None,
+            text: text.into(),
+            unit: None,
+            style: Style::default(),
+            alignment: Alignment::Center,
+        }
+    }
+
+    /// Sets a unit shown in normal text after the digits, e.g. `"LUFS"`.
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Sets the style of the digits.
+    pub fn style(mut self, style: impl Into<Style>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Sets the horizontal alignment of the digits.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Surrounds the `BigNumber` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// The glyphs of the text, or `None` if the text contains characters without a big glyph.
+    fn glyphs(&self) -> Option<Vec<[&'static str; GLYPH_HEIGHT as usize]>> {
+        self.text.chars().map(glyph).collect()
+    }
+
+    /// The width of the big text including a column of spacing between glyphs and the unit.
+    fn width(glyphs: &[[&str; GLYPH_HEIGHT as usize]], unit: Option<&str>) -> u16 {
+        let digits: usize = glyphs.iter().map(|g| g[0].chars().count() + 1).sum();
+        let unit = unit.map_or(0, |u| u.chars().count());
+        (digits + unit) as u16
+    }
+
+    fn render_small(&self, area: Rect, buf: &mut Buffer) {
+        let text = match &self.unit {
+            Some(unit) => format!("{} {}", self.text, unit),
+            None => self.text.clone(),
+        };
+        let y_offset = area.height.saturating_sub(1) / 2;
+        Paragraph::new(text)
+            .style(self.style)
+            .alignment(self.alignment)
+            .render(Rect::new(area.x, area.y + y_offset, area.width, 1), buf);
+    }
+}
+
+impl Widget for BigNumber<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.is_empty() {
+            return;
+        }
+
+        let Some(glyphs) = self.glyphs() else {
+            self.render_small(inner, buf);
+            return;
+        };
+        let width = Self::width(&glyphs, self.unit.as_deref());
+        if inner.height < GLYPH_HEIGHT || inner.width < width {
+            self.render_small(inner, buf);
+            return;
+        }
+
+        let mut x = match self.alignment {
+            Alignment::Left => inner.left(),
+            Alignment::Center => inner.left() + (inner.width - width) / 2,
+            Alignment::Right => inner.right() - width,
+        };
+        let y = inner.top() + (inner.height - GLYPH_HEIGHT) / 2;
+
+        for glyph in &glyphs {
+            for (row, line) in glyph.iter().enumerate() {
+                buf.set_string(x, y + row as u16, line, self.style);
+            }
+            x += glyph[0].chars().count() as u16 + 1;
+        }
+        if let Some(unit) = &self.unit {
+            buf.set_string(x, y + GLYPH_HEIGHT - 1, unit, Style::default());
+        }
+    }
+}
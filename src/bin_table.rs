@@ -0,0 +1,134 @@
+use ratatui::{
+    layout::Constraint,
+    prelude::{Buffer, Rect, Widget},
+    style::{Style, Stylize},
+    widgets::{Block, Row, StatefulWidget, Table, TableState},
+};
+
+use crate::{
+    music::{frequency_to_midi, note_name},
+    trace::FrequencyPoint,
+};
+
+/// A table listing the strongest peaks of an FFT spectrum with their frequency, level and nearest
+/// note, for reading precise numbers rather than graphics.
+///
+/// Peaks are local maxima of the spectrum, refined between bins with parabolic interpolation, and
+/// listed from strongest to weakest.
+#[derive(Debug, Clone)]
+pub struct BinTable<'a> {
+    /// The block that surrounds the table, if any.
+    block: Option<Block<'a>>,
+    /// The linear magnitude of each FFT bin.
+    magnitudes: &'a [f32],
+    /// The width of each bin in Hz.
+    bin_width_hz: f32,
+    /// The largest number of peaks to list.
+    count: usize,
+    /// The magnitude at or below which a bin counts as silent.
+    magnitude_floor: f32,
+    /// The index of the highlighted row, if any.
+    selected: Option<usize>,
+}
+
+impl<'a> BinTable<'a> {
+    /// Creates a new `BinTable` for a linear magnitude spectrum, e.g. the output of an FFT where
+    /// bin `k` is centered at `k * bin_width_hz`. Lists up to 10 peaks.
+    pub fn new(magnitudes: &'a [f32], bin_width_hz: f32) -> Self {
+        BinTable {
+            block: None,
+            magnitudes,
+            bin_width_hz,
+            count: 10,
+            magnitude_floor: 1e-12,
+            selected: None,
+        }
+    }
+
+    /// Sets the largest number of peaks to list.
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Sets the magnitude at or below which a bin counts as silent, so it is never listed as a
+    /// peak. Defaults to 1e-12, -240 dB.
+    pub fn magnitude_floor(mut self, floor: f32) -> Self {
+        self.magnitude_floor = floor;
+        self
+    }
+
+    /// Highlights the row at `index`.
+    pub fn select(mut self, index: Option<usize>) -> Self {
+        self.selected = index;
+        self
+    }
+
+    /// Surrounds the `BinTable` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// The listed peaks, strongest first, with their level in dBFS.
+    pub fn peaks(&self) -> Vec<FrequencyPoint> {
+        let db: Vec<f32> = self
+            .magnitudes
+            .iter()
+            .map(|magnitude| 20.0 * magnitude.abs().max(self.magnitude_floor).log10())
+            .collect();
+        let floor_db = 20.0 * self.magnitude_floor.log10();
+
+        // Skip the DC bin, which is not a peak of any tone.
+        let mut peaks: Vec<FrequencyPoint> = (1..db.len().saturating_sub(1))
+            .filter(|&k| db[k] > floor_db && db[k] > db[k - 1] && db[k] >= db[k + 1])
+            .map(|k| {
+                let (a, b, c) = (db[k - 1], db[k], db[k + 1]);
+                let denominator = a - 2.0 * b + c;
+                let offset = if denominator.abs() > f32::EPSILON {
+                    (0.5 * (a - c) / denominator).clamp(-0.5, 0.5)
+                } else {
+                    0.0
+                };
+                FrequencyPoint::new(
+                    (k as f32 + offset) * self.bin_width_hz,
+                    b - 0.25 * (a - c) * offset,
+                )
+            })
+            .filter(|peak| peak.magnitude_db.is_finite())
+            .collect();
+        peaks.sort_by(|a, b| b.magnitude_db.total_cmp(&a.magnitude_db));
+        peaks.truncate(self.count);
+        peaks
+    }
+}
+
+impl Widget for BinTable<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let rows = self.peaks().into_iter().map(|peak| {
+            let note = note_name(frequency_to_midi(peak.frequency).round() as i32);
+            Row::new([
+                format!("{:.1} Hz", peak.frequency),
+                format!("{:.1} dB", peak.magnitude_db),
+                note,
+            ])
+        });
+
+        let mut table = Table::new(
+            rows,
+            [
+                Constraint::Length(11),
+                Constraint::Length(9),
+                Constraint::Length(4),
+            ],
+        )
+        .header(Row::new(["Frequency", "Level", "Note"]).bold())
+        .row_highlight_style(Style::new().reversed());
+        if let Some(block) = self.block {
+            table = table.block(block);
+        }
+        let mut state = TableState::default().with_selected(self.selected);
+        StatefulWidget::render(table, area, buf, &mut state);
+    }
+}
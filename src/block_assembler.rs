@@ -0,0 +1,191 @@
+/// Assembles audio callbacks of arbitrary size into fixed-size, overlapping analysis frames.
+///
+/// Samples are pushed as they arrive from the audio backend, and complete frames are pulled with
+/// [`BlockAssembler::next_frame`]. Consecutive frames start `hop_size` samples apart, so a hop
+/// size smaller than the frame size gives overlapping frames.
+///
+/// ```
+/// use rataudio_rta::BlockAssembler;
+///
+/// let mut assembler = BlockAssembler::new(4, 2);
+/// assembler.push(&[0.0, 1.0, 2.0]);
+/// assert_eq!(assembler.next_frame(), None);
+///
+/// assembler.push(&[3.0, 4.0, 5.0]);
+/// assert_eq!(assembler.next_frame(), Some(&[0.0, 1.0, 2.0, 3.0][..]));
+/// assert_eq!(assembler.next_frame(), Some(&[2.0, 3.0, 4.0, 5.0][..]));
+/// assert_eq!(assembler.next_frame(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockAssembler {
+    /// The number of samples in each frame.
+    frame_size: usize,
+    /// The number of samples between the start of consecutive frames.
+    hop_size: usize,
+    /// Samples that have not yet been fully consumed by a frame.
+    buffer: Vec<f32>,
+    /// Whether the frame at the start of the buffer has been handed out and should be advanced.
+    frame_taken: bool,
+}
+
+impl BlockAssembler {
+    /// Creates a new `BlockAssembler` producing frames of `frame_size` samples, `hop_size` apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hop_size` is zero or larger than `frame_size`, as that would leave gaps between
+    /// frames.
+    pub fn new(frame_size: usize, hop_size: usize) -> Self {
+        assert!(
+            hop_size > 0 && hop_size <= frame_size,
+            "hop size must be between 1 and the frame size"
+        );
+        BlockAssembler {
+            frame_size,
+            hop_size,
+            buffer: Vec::with_capacity(frame_size * 2),
+            frame_taken: false,
+        }
+    }
+
+    /// Creates a new `BlockAssembler` producing frames of `frame_size` samples every `hop_ms`
+    /// milliseconds at `sample_rate`, independent of the frame size.
+    ///
+    /// The overlap follows from the two, e.g. 16384 sample frames every 16.7 ms at 48 kHz give 60
+    /// frames per second with about 95% overlap. Hops longer than a frame are shortened to the
+    /// frame size, and hops shorter than a sample are lengthened to one sample.
+    ///
+    /// ```
+    /// use rataudio_rta::BlockAssembler;
+    ///
+    /// let assembler = BlockAssembler::with_hop_ms(16384, 1000.0 / 60.0, 48000.0);
+    /// assert_eq!(assembler.hop_size(), 800);
+    /// ```
+    pub fn with_hop_ms(frame_size: usize, hop_ms: f32, sample_rate: f32) -> Self {
+        let hop_size = (hop_ms / 1000.0 * sample_rate).round() as usize;
+        Self::new(frame_size, hop_size.clamp(1, frame_size.max(1)))
+    }
+
+    /// The number of samples in each frame.
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// The number of samples between the start of consecutive frames.
+    pub fn hop_size(&self) -> usize {
+        self.hop_size
+    }
+
+    /// The fraction of each frame shared with the next frame, from 0.0 (no overlap) to just
+    /// below 1.0.
+    pub fn overlap(&self) -> f32 {
+        1.0 - self.hop_size as f32 / self.frame_size as f32
+    }
+
+    /// Appends samples received from the audio callback.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn push(&mut self, samples: &[f32]) {
+        self.buffer.extend_from_slice(samples);
+    }
+
+    /// Returns the next complete frame, or `None` if more samples are needed.
+    pub fn next_frame(&mut self) -> Option<&[f32]> {
+        if self.frame_taken {
+            self.buffer.drain(..self.hop_size);
+            self.frame_taken = false;
+        }
+        if self.buffer.len() < self.frame_size {
+            return None;
+        }
+        self.frame_taken = true;
+        Some(&self.buffer[..self.frame_size])
+    }
+
+    /// Discards all buffered samples, e.g. after the input device changed.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.frame_taken = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pushes `0.0, 1.0, 2.0, ...` in callbacks of the given sizes and returns the start of each
+    /// frame, checking that every frame holds consecutive samples.
+    fn frame_starts(assembler: &mut BlockAssembler, callback_sizes: &[usize]) -> Vec<usize> {
+        let frame_size = assembler.frame_size();
+        let mut next_sample = 0;
+        let mut starts = Vec::new();
+        for &size in callback_sizes {
+            let samples: Vec<f32> = (next_sample..next_sample + size)
+                .map(|n| n as f32)
+                .collect();
+            next_sample += size;
+            assembler.push(&samples);
+            while let Some(frame) = assembler.next_frame() {
+                let start = frame[0] as usize;
+                let expected: Vec<f32> = (start..start + frame_size).map(|n| n as f32).collect();
+                assert_eq!(frame, expected);
+                starts.push(start);
+            }
+        }
+        starts
+    }
+
+    #[test]
+    fn frames_callbacks_that_are_not_multiples_of_the_hop() {
+        let mut assembler = BlockAssembler::new(8, 3);
+        let starts = frame_starts(&mut assembler, &[5, 1, 7, 2, 11]);
+        // 26 samples hold frames starting every 3 samples up to sample 18.
+        assert_eq!(starts, [0, 3, 6, 9, 12, 15, 18]);
+    }
+
+    #[test]
+    fn hop_of_a_frame_gives_adjacent_frames() {
+        let mut assembler = BlockAssembler::new(4, 4);
+        assert_eq!(assembler.overlap(), 0.0);
+        assert_eq!(frame_starts(&mut assembler, &[3, 3, 3, 3]), [0, 4, 8]);
+    }
+
+    #[test]
+    fn reset_discards_buffered_samples() {
+        let mut assembler = BlockAssembler::new(4, 2);
+        assembler.push(&[9.0; 6]);
+        assert!(assembler.next_frame().is_some());
+        assembler.reset();
+        assert_eq!(assembler.next_frame(), None);
+        assembler.push(&[0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(assembler.next_frame(), Some(&[0.0, 1.0, 2.0, 3.0][..]));
+        assert_eq!(assembler.next_frame(), None);
+    }
+
+    #[test]
+    fn hop_ms_is_rounded_to_samples() {
+        let hop_size = |hop_ms| BlockAssembler::with_hop_ms(1024, hop_ms, 48000.0).hop_size();
+        assert_eq!(hop_size(0.1), 5);
+        assert_eq!(hop_size(0.21), 10);
+        assert_eq!(hop_size(0.0), 1);
+        assert_eq!(hop_size(-5.0), 1);
+        assert_eq!(hop_size(1000.0), 1024);
+    }
+
+    #[test]
+    fn no_samples_are_lost_or_duplicated() {
+        // Irregular callback sizes from a small linear congruential generator.
+        let mut seed = 12345_u32;
+        let callback_sizes: Vec<usize> = (0..500)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as usize % 700
+            })
+            .collect();
+        let total: usize = callback_sizes.iter().sum();
+
+        let mut assembler = BlockAssembler::new(512, 173);
+        let starts = frame_starts(&mut assembler, &callback_sizes);
+        let expected: Vec<usize> = (0..=(total - 512) / 173).map(|k| k * 173).collect();
+        assert_eq!(starts, expected);
+    }
+}
@@ -0,0 +1,163 @@
+use std::{io, path::Path};
+
+use crate::{
+    import::{parse_frequency_response, read_frequency_response},
+    rta::Band,
+    trace::{FrequencyPoint, interpolate_db, resample_curve},
+};
+
+/// The frequency response of a measurement microphone, for correcting its readings.
+///
+/// Load the calibration file that comes with the microphone, then apply the inverse of its
+/// response to each analyzed spectrum with [`MicCalibration::apply`]. The gain of each band is
+/// resampled from the points of the file with [`crate::resample_curve`], and held at the gain of
+/// the first or last point outside the range they cover.
+///
+/// ```
+/// use rataudio_rta::{Band, MicCalibration};
+///
+/// let calibration = MicCalibration::parse("\"Sens Factor =-1.2dB\"\n20 -2.0\n20000 3.0").unwrap();
+/// let mut bands = vec![Band::new(0.5, 20), Band::new(0.5, 20000)];
+/// calibration.apply(&mut bands, -60.0);
+/// assert!((bands[0].get_db(-60.0) - -28.0).abs() < 1e-3);
+/// assert!((bands[1].get_db(-60.0) - -33.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicCalibration {
+    /// The gain of the microphone in dB by frequency, sorted by frequency.
+    points: Vec<FrequencyPoint>,
+}
+
+impl MicCalibration {
+    /// Creates a new `MicCalibration` from the gain of the microphone in dB by frequency.
+    ///
+    /// Returns `None` if there are no points.
+    pub fn new(mut points: Vec<FrequencyPoint>) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        points.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        Some(MicCalibration { points })
+    }
+
+    /// Reads a calibration file of frequency and gain pairs.
+    ///
+    /// See [`MicCalibration::parse`] for the accepted format.
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(MicCalibration {
+            points: read_frequency_response(path)?,
+        })
+    }
+
+    /// Parses the text of a calibration file of frequency and gain pairs.
+    ///
+    /// Each data line holds a frequency in Hz and a gain in dB, separated by whitespace, commas or
+    /// semicolons. Further columns, such as phase, are ignored. Lines that don't start with a
+    /// number, such as the sensitivity line of common USB measurement microphones, are skipped.
+    pub fn parse(content: &str) -> io::Result<Self> {
+        Ok(MicCalibration {
+            points: parse_frequency_response(content)?,
+        })
+    }
+
+    /// The points of the calibration, sorted by frequency.
+    pub fn points(&self) -> &[FrequencyPoint] {
+        &self.points
+    }
+
+    /// The gain of the microphone in dB at `frequency` in Hz.
+    pub fn gain_at(&self, frequency: f32) -> f32 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if frequency <= first.frequency {
+            first.magnitude_db
+        } else if frequency >= last.frequency {
+            last.magnitude_db
+        } else {
+            interpolate_db(&self.points, frequency).unwrap_or(last.magnitude_db)
+        }
+    }
+
+    /// The gain of the microphone in dB at each band, or NaN for bands without a frequency.
+    pub fn gains(&self, bands: &[Band]) -> Vec<f32> {
+        resample_curve(&self.points, bands)
+            .into_iter()
+            .zip(bands)
+            .map(|(gain, band)| match band.frequency {
+                Some(frequency) if gain.is_nan() && frequency > 0 => self.gain_at(frequency as f32),
+                _ => gain,
+            })
+            .collect()
+    }
+
+    /// Corrects the bands, on a scale starting at `min_db`, for the response of the microphone by
+    /// subtracting its gain at each band, see [`MicCalibration::gains`].
+    ///
+    /// Bands without a frequency and empty, silent or invalid bands are left unchanged, so the
+    /// correction doesn't lift the floor of the meter.
+    pub fn apply(&self, bands: &mut [Band], min_db: f32) {
+        let gains = self.gains(bands);
+        for (band, gain) in bands.iter_mut().zip(gains) {
+            if gain.is_nan() || band.silent || band.invalid || band.value <= 0.0 {
+                continue;
+            }
+            band.set_db(band.get_db(min_db) - gain, min_db);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALIBRATION: &str = "\
+\"Sens Factor =-1.2dB, SERNO: 7000123\"
+* Freq(Hz) SPL(dB) Phase(degrees)
+20 -2.0 0.0
+1000 0.0
+20000 4.0
+";
+
+    #[test]
+    fn skips_comment_and_header_lines() {
+        let calibration = MicCalibration::parse(CALIBRATION).unwrap();
+        assert_eq!(
+            calibration.points(),
+            [
+                FrequencyPoint::new(20.0, -2.0).with_phase(0.0),
+                FrequencyPoint::new(1000.0, 0.0),
+                FrequencyPoint::new(20000.0, 4.0),
+            ]
+        );
+        assert!(MicCalibration::parse("\"Sens Factor =-1.2dB\"\n").is_err());
+    }
+
+    #[test]
+    fn interpolates_and_holds_the_end_points() {
+        let calibration = MicCalibration::parse(CALIBRATION).unwrap();
+        assert_eq!(calibration.gain_at(1000.0), 0.0);
+        // 4472 Hz lies halfway between 1 and 20 kHz on a logarithmic axis.
+        assert!((calibration.gain_at(4472.0) - 2.0).abs() < 1e-3);
+        assert_eq!(calibration.gain_at(10.0), -2.0);
+        assert_eq!(calibration.gain_at(0.0), -2.0);
+        assert_eq!(calibration.gain_at(24000.0), 4.0);
+    }
+
+    #[test]
+    fn apply_leaves_bands_without_frequency_unchanged() {
+        let calibration = MicCalibration::parse(CALIBRATION).unwrap();
+        let mut bands = vec![
+            Band {
+                frequency: None,
+                ..Band::new(0.5, 0)
+            },
+            Band::new(0.5, 10),
+            Band::new(0.0, 20000),
+        ];
+        calibration.apply(&mut bands, -60.0);
+        assert_eq!(bands[0].value, 0.5);
+        // Below the first point, the gain of the first point is removed.
+        assert!((bands[1].get_db(-60.0) - -28.0).abs() < 1e-3);
+        // Empty bands stay empty.
+        assert_eq!(bands[2].value, 0.0);
+    }
+}
@@ -0,0 +1,114 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::{Color, Style},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    music::{Chroma, NOTE_NAMES},
+    rendering::PARTIAL_BLOCKS,
+};
+
+/// A widget showing the relative energy of the 12 pitch classes of a [`Chroma`] as bars.
+///
+/// The strongest pitch class is highlighted, which helps when determining the key of music.
+#[derive(Debug, Clone)]
+pub struct Chromagram<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The pitch class energies to display.
+    chroma: &'a Chroma,
+    /// The color of the bars.
+    color: Color,
+    /// The color of the strongest pitch class.
+    highlight_color: Color,
+}
+
+impl<'a> Chromagram<'a> {
+    /// Creates a new `Chromagram` displaying `chroma`.
+    pub fn new(chroma: &'a Chroma) -> Self {
+        Chromagram {
+            block: None,
+            chroma,
+            color: Color::Cyan,
+            highlight_color: Color::Red,
+        }
+    }
+
+    /// Sets the color of the bars.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the color of the strongest pitch class.
+    pub fn highlight_color(mut self, color: Color) -> Self {
+        self.highlight_color = color;
+        self
+    }
+
+    /// Surrounds the `Chromagram` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for Chromagram<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.height < 2 || inner.width < NOTE_NAMES.len() as u16 {
+            return;
+        }
+
+        let [bars_area, label_area] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(inner);
+        let columns = Layout::horizontal([Constraint::Fill(1); 12]).split(bars_area);
+        let labels = Layout::horizontal([Constraint::Fill(1); 12]).split(label_area);
+        let strongest = self.chroma.strongest();
+
+        for (i, (ratio, (column, label))) in self
+            .chroma
+            .normalized()
+            .into_iter()
+            .zip(columns.iter().zip(labels.iter()))
+            .enumerate()
+        {
+            let color = if strongest == Some(i) {
+                self.highlight_color
+            } else {
+                self.color
+            };
+
+            // Leave a gap between the bars when there is room for it.
+            let width = if column.width > 2 {
+                column.width - 1
+            } else {
+                column.width
+            };
+            let eighths = (ratio * column.height as f32 * 8.0).round() as u16;
+            for row in 0..eighths.div_ceil(8) {
+                let symbol = match eighths - row * 8 {
+                    filled @ 1..8 => PARTIAL_BLOCKS[filled as usize],
+                    _ => ratatui::symbols::bar::FULL,
+                };
+                for x in column.left()..column.left() + width {
+                    buf[(x, column.bottom() - 1 - row)]
+                        .set_symbol(symbol)
+                        .set_fg(color);
+                }
+            }
+
+            Paragraph::new(NOTE_NAMES[i])
+                .style(Style::new().fg(color))
+                .alignment(Alignment::Left)
+                .render(*label, buf);
+        }
+    }
+}
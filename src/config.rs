@@ -0,0 +1,302 @@
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    averaging::BandAverager,
+    rta::{Band, DisplayScale, RTA},
+};
+
+/// Configuration for an `RTA` widget, loadable from a TOML file.
+///
+/// ```toml
+/// min_db = -90.0
+/// display_scale = "Decibel"
+/// averaging = false
+/// show_peak_labels = true
+/// band_color = "yellow"
+///
+/// [[bands]]
+/// frequency = 63
+///
+/// [[bands]]
+/// frequency = 125
+/// color = "#ff8800"
+/// ```
+///
+/// Colors are names like `"red"` or `"light-blue"`, hex codes like `"#ff8800"` or `"#f80"`,
+/// `"rgb(255, 136, 0)"`, or palette indices like `"indexed(208)"`.
+///
+/// The input device isn't part of the configuration, since the app captures the audio and feeds
+/// the bands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RtaConfig {
+    /// The lowest value of the dB scale.
+    pub min_db: f32,
+    /// How levels are mapped to the height of the bars.
+    pub display_scale: DisplayScale,
+    /// Whether the bands are smoothed by averaging them over time, see [`RtaConfig::averager`].
+    pub averaging: bool,
+    /// Whether to show the peak labels at the top of the meter.
+    pub show_peak_labels: bool,
+    /// The color used for bands that don't specify their own.
+    #[serde(with = "color")]
+    pub band_color: Color,
+    /// The frequency bands that make up the RTA meter.
+    pub bands: Vec<BandConfig>,
+}
+
+/// Configuration of a single frequency band.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BandConfig {
+    /// The center frequency of the band in Hz.
+    pub frequency: u16,
+    /// The color of the band, overriding `RtaConfig::band_color`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "optional_color"
+    )]
+    pub color: Option<Color>,
+}
+
+/// Errors that can occur while loading or saving a configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file could not be read or written.
+    Io(io::Error),
+    /// The config file is not valid TOML or doesn't match the expected layout.
+    Parse(toml::de::Error),
+    /// The configuration could not be converted to TOML.
+    Serialize(toml::ser::Error),
+    /// The config doesn't define any bands.
+    NoBands,
+    /// The lowest value of the dB scale isn't a finite, negative number.
+    InvalidMinDb(f32),
+}
+
+impl Default for RtaConfig {
+    fn default() -> Self {
+        RtaConfig {
+            min_db: -90.0,
+            display_scale: DisplayScale::default(),
+            averaging: false,
+            show_peak_labels: true,
+            band_color: Color::Yellow,
+            bands: Vec::new(),
+        }
+    }
+}
+
+impl RtaConfig {
+    /// Loads the configuration from the TOML file at `path`.
+    pub fn from_toml(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Parses the configuration from a TOML string.
+    ///
+    /// Fails if the config defines no bands or `min_db` isn't a finite, negative number.
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        let config: RtaConfig = toml::from_str(content).map_err(ConfigError::Parse)?;
+        if config.bands.is_empty() {
+            return Err(ConfigError::NoBands);
+        }
+        if !config.min_db.is_finite() || config.min_db >= 0.0 {
+            return Err(ConfigError::InvalidMinDb(config.min_db));
+        }
+        Ok(config)
+    }
+
+    /// Saves the configuration as a TOML file at `path`, e.g. as a preset to load with
+    /// [`RtaConfig::from_toml`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let content = toml::to_string(self).map_err(ConfigError::Serialize)?;
+        fs::write(path, content).map_err(ConfigError::Io)
+    }
+
+    /// Creates the bands described by the configuration, all starting at zero.
+    pub fn bands(&self) -> Vec<Band> {
+        self.bands
+            .iter()
+            .map(|band| Band {
+                color: band.color.unwrap_or(self.band_color),
+                ..Band::new(0.0, band.frequency)
+            })
+            .collect()
+    }
+
+    /// Creates an `RTA` widget configured according to this configuration.
+    pub fn build<'a>(&self) -> RTA<'a> {
+        RTA::new(self.bands(), self.min_db)
+            .display_scale(self.display_scale)
+            .show_peak_labels(self.show_peak_labels)
+    }
+
+    /// Creates a [`BandAverager`] for the bands if averaging is enabled. Push each analyzed frame
+    /// to it and apply it to the bands before building the widget.
+    pub fn averager(&self) -> Option<BandAverager> {
+        self.averaging.then(|| BandAverager::new(self.min_db))
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "failed to access config file: {err}"),
+            ConfigError::Parse(err) => write!(f, "invalid config: {err}"),
+            ConfigError::Serialize(err) => write!(f, "failed to serialize config: {err}"),
+            ConfigError::NoBands => write!(f, "config doesn't define any bands"),
+            ConfigError::InvalidMinDb(min_db) => {
+                write!(f, "min_db must be a negative number of dB, got {min_db}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Parse(err) => Some(err),
+            ConfigError::Serialize(err) => Some(err),
+            ConfigError::NoBands | ConfigError::InvalidMinDb(_) => None,
+        }
+    }
+}
+
+/// Parses a color from a config file, see [`RtaConfig`] for the accepted formats.
+fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim();
+    let arguments = |name: &str| {
+        text.strip_prefix(name)?
+            .trim_start()
+            .strip_prefix('(')?
+            .strip_suffix(')')
+            .map(|arguments| arguments.split(',').map(str::trim))
+    };
+    if let Some(mut arguments) = arguments("indexed") {
+        let index = arguments.next()?.parse().ok()?;
+        return arguments.next().is_none().then_some(Color::Indexed(index));
+    }
+    if let Some(arguments) = arguments("rgb") {
+        let components: Vec<u8> = arguments
+            .map(|component| component.parse().ok())
+            .collect::<Option<_>>()?;
+        return match components[..] {
+            [r, g, b] => Some(Color::Rgb(r, g, b)),
+            _ => None,
+        };
+    }
+    if let Some(hex) = text.strip_prefix('#')
+        && hex.len() == 3
+    {
+        let digit = |i: usize| {
+            u8::from_str_radix(hex.get(i..i + 1)?, 16)
+                .ok()
+                .map(|d| d * 17)
+        };
+        return Some(Color::Rgb(digit(0)?, digit(1)?, digit(2)?));
+    }
+    Color::from_str(text).ok()
+}
+
+/// Serializes colors in the format of [`Color`]'s `Display` and deserializes them with
+/// [`parse_color`], reporting the accepted formats on errors.
+pub(crate) mod color {
+    use ratatui::style::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    pub(crate) fn serialize<S: Serializer>(
+        color: &Color,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Color, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        super::parse_color(&text).ok_or_else(|| {
+            D::Error::custom(format!(
+                "invalid color \"{text}\", expected a name like \"red\", a hex code like \"#ff8800\", \
+                 \"rgb(255, 136, 0)\" or \"indexed(208)\""
+            ))
+        })
+    }
+}
+
+/// Like [`color`], for optional colors.
+pub(crate) mod optional_color {
+    use ratatui::style::Color;
+    use serde::{Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => super::color::serialize(color, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        super::color::deserialize(deserializer).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scale_and_averaging() {
+        let config = RtaConfig::from_toml_str(
+            "display_scale = \"Percent\"\naveraging = true\n[[bands]]\nfrequency = 1000\n",
+        )
+        .unwrap();
+        assert_eq!(config.display_scale, DisplayScale::Percent);
+        assert!(config.averager().is_some());
+        assert_eq!(config.build().display_scale, DisplayScale::Percent);
+    }
+
+    #[test]
+    fn defaults_to_decibel_scale_without_averaging() {
+        let config = RtaConfig::from_toml_str("[[bands]]\nfrequency = 1000\n").unwrap();
+        assert_eq!(config.display_scale, DisplayScale::Decibel);
+        assert!(config.averager().is_none());
+    }
+
+    #[test]
+    fn rejects_min_db_that_is_not_negative() {
+        for min_db in ["0.0", "6.0", "nan", "-inf"] {
+            let result = RtaConfig::from_toml_str(&format!(
+                "min_db = {min_db}\n[[bands]]\nfrequency = 1000\n"
+            ));
+            assert!(
+                matches!(result, Err(ConfigError::InvalidMinDb(_))),
+                "{min_db}: {result:?}"
+            );
+        }
+        let config =
+            RtaConfig::from_toml_str("min_db = -60.0\n[[bands]]\nfrequency = 1000\n").unwrap();
+        assert_eq!(config.min_db, -60.0);
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("#f80"), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("rgb(1, 2, 3)"), Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(parse_color("indexed(208)"), Some(Color::Indexed(208)));
+        assert_eq!(parse_color("light-blue"), Some(Color::LightBlue));
+        assert_eq!(parse_color("#12345"), None);
+    }
+}
@@ -0,0 +1,339 @@
+use std::f32::consts::SQRT_2;
+
+use ratatui::{
+    layout::Constraint,
+    prelude::{Buffer, Rect, Widget},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Row, Table},
+};
+
+use crate::filter::Biquad;
+
+/// Octave band center frequencies used for per-band decay analysis.
+const OCTAVE_CENTERS: [u16; 8] = [63, 125, 250, 500, 1000, 2000, 4000, 8000];
+
+/// How far the end of a fit range must stay above the noise floor of the response, in dB.
+const NOISE_MARGIN_DB: f32 = 10.0;
+
+/// Computes the Schroeder backward-integrated energy decay curve of an impulse response.
+///
+/// The curve is in dB relative to the total energy, so it starts at 0 dB and decreases
+/// monotonically.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn schroeder_curve(samples: &[f32]) -> Vec<f32> {
+    let mut remaining = 0.0_f64;
+    let mut curve: Vec<f64> = samples
+        .iter()
+        .rev()
+        .map(|s| {
+            remaining += (*s as f64).powi(2);
+            remaining
+        })
+        .collect();
+    curve.reverse();
+
+    let total = curve.first().copied().unwrap_or(0.0);
+    if total <= 0.0 {
+        return vec![f32::NEG_INFINITY; samples.len()];
+    }
+    curve
+        .iter()
+        .map(|e| (10.0 * (e / total).log10()) as f32)
+        .collect()
+}
+
+/// Reverberation times estimated from the energy decay of an impulse response.
+///
+/// All times are in seconds and extrapolated to a 60 dB decay. The response is truncated where its
+/// decay disappears into the noise floor, found with the iterative method of Lundeby et al., so
+/// the noise doesn't flatten the decay curve. A value is `None` when the end of its fit range
+/// isn't at least 10 dB above the noise floor, e.g. T30 needs a decay of 45 dB above the noise.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DecayTimes {
+    /// The octave band center frequency, or `None` for the broadband response.
+    pub frequency: Option<u16>,
+    /// Early decay time, fitted from 0 to -10 dB.
+    pub edt: Option<f32>,
+    /// Reverberation time fitted from -5 to -25 dB.
+    pub t20: Option<f32>,
+    /// Reverberation time fitted from -5 to -35 dB.
+    pub t30: Option<f32>,
+}
+
+impl DecayTimes {
+    /// Estimates the decay times of the broadband impulse response.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn from_impulse_response(samples: &[f32], sample_rate: f32) -> Self {
+        let (samples, noise_db) = match noise_truncation(samples, sample_rate) {
+            Some((end, noise_db)) => (&samples[..end], noise_db),
+            None => (samples, f32::NEG_INFINITY),
+        };
+        let curve = schroeder_curve(samples);
+        let fit = |start_db: f32, end_db: f32| {
+            decay_time(&curve, sample_rate, start_db, end_db)
+                .filter(|_| end_db - NOISE_MARGIN_DB >= noise_db)
+        };
+        DecayTimes {
+            frequency: None,
+            edt: fit(0.0, -10.0),
+            t20: fit(-5.0, -25.0),
+            t30: fit(-5.0, -35.0),
+        }
+    }
+
+    /// Estimates the decay times in each octave band from 63 Hz to 8 kHz, skipping bands too close
+    /// to the Nyquist frequency.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn per_octave(samples: &[f32], sample_rate: f32) -> Vec<Self> {
+        OCTAVE_CENTERS
+            .iter()
+            .filter(|&&center| (center as f32) * SQRT_2 < sample_rate / 2.0)
+            .map(|&center| {
+                // Two cascaded sections give a steeper roll-off than a single band-pass.
+                let mut filters = [
+                    Biquad::band_pass(center as f32, SQRT_2, sample_rate),
+                    Biquad::band_pass(center as f32, SQRT_2, sample_rate),
+                ];
+                let filtered: Vec<f32> = samples
+                    .iter()
+                    .map(|&s| filters.iter_mut().fold(s, |x, f| f.process_sample(x)))
+                    .collect();
+                DecayTimes {
+                    frequency: Some(center),
+                    ..Self::from_impulse_response(&filtered, sample_rate)
+                }
+            })
+            .collect()
+    }
+
+    /// The best available RT60 estimate, which is T30 if available and T20 otherwise.
+    pub fn rt60(&self) -> Option<f32> {
+        self.t30.or(self.t20)
+    }
+}
+
+/// Fits a line to the decay curve between `start_db` and `end_db` and extrapolates it to 60 dB.
+fn decay_time(curve: &[f32], sample_rate: f32, start_db: f32, end_db: f32) -> Option<f32> {
+    let start = curve.iter().position(|&db| db <= start_db)?;
+    let end = curve.iter().position(|&db| db <= end_db)?;
+    if end <= start + 1 {
+        return None;
+    }
+
+    // Least squares regression of level (dB) against time (s).
+    let (slope, _) = fit_line(
+        curve[start..=end]
+            .iter()
+            .enumerate()
+            .map(|(i, &db)| ((start + i) as f64 / sample_rate as f64, db as f64)),
+    )?;
+    if slope >= 0.0 {
+        return None;
+    }
+    Some((-60.0 / slope) as f32)
+}
+
+/// Fits a line to `(x, y)` points with least squares and returns its slope and intercept.
+fn fit_line(points: impl Iterator<Item = (f64, f64)> + Clone) -> Option<(f64, f64)> {
+    let n = points.clone().count() as f64;
+    let (sum_x, sum_y) = points
+        .clone()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    let (mean_x, mean_y) = (sum_x / n, sum_y / n);
+    let (covariance, variance) = points.fold((0.0, 0.0), |(c, v), (x, y)| {
+        let dx = x - mean_x;
+        (c + dx * (y - mean_y), v + dx * dx)
+    });
+    let slope = covariance / variance;
+    slope
+        .is_finite()
+        .then_some((slope, mean_y - slope * mean_x))
+}
+
+/// Finds where the decay of an impulse response disappears into its noise floor, with a
+/// simplified version of the iterative method of Lundeby et al.
+///
+/// Returns the sample index to truncate the response at and the level of the noise in dB relative
+/// to the loudest part of the response, or `None` if the response has no noise, is too short or
+/// doesn't decay.
+fn noise_truncation(samples: &[f32], sample_rate: f32) -> Option<(usize, f32)> {
+    /// The number of refinements of the truncation point.
+    const ITERATIONS: usize = 5;
+
+    let energy: Vec<f64> = samples.iter().map(|&s| (s as f64).powi(2)).collect();
+    let mean_db = |range: &[f64]| 10.0 * (range.iter().sum::<f64>() / range.len() as f64).log10();
+
+    // The level of the response, smoothed in blocks of 10 ms.
+    let block = ((sample_rate * 0.01) as usize).max(1);
+    let levels: Vec<f64> = energy.chunks(block).map(mean_db).collect();
+    let (peak, &peak_db) = levels
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    if levels.len() < peak + 3 || !peak_db.is_finite() {
+        return None;
+    }
+
+    // Start with the noise in the last tenth of the response.
+    let tail = energy.len() - (energy.len() / 10).max(1);
+    let mut noise_db = mean_db(&energy[tail..]);
+    if noise_db == f64::NEG_INFINITY {
+        return None;
+    }
+    let mut crossing = energy.len();
+    for _ in 0..ITERATIONS {
+        // Fit the decay from its peak to 10 dB above the noise.
+        let end = (peak..levels.len())
+            .find(|&i| levels[i] < noise_db + 10.0)
+            .unwrap_or(levels.len());
+        if end < peak + 2 {
+            break;
+        }
+        let (slope, intercept) =
+            fit_line((peak..end).map(|i| (((i as f64) + 0.5) * block as f64, levels[i])))?;
+        if slope >= 0.0 {
+            return None;
+        }
+        crossing = (((noise_db - intercept) / slope).max(0.0) as usize).min(energy.len());
+
+        // Measure the noise from 10 dB of decay after the crossing, but in at least the last
+        // tenth of the response.
+        let noise_start = ((crossing as f64 - 10.0 / slope) as usize).min(tail);
+        noise_db = mean_db(&energy[noise_start..]);
+    }
+    Some((crossing, (noise_db - peak_db) as f32))
+}
+
+/// A table widget listing the decay times of each band.
+#[derive(Debug, Clone)]
+pub struct DecayTable<'a> {
+    /// The block that surrounds the table, if any.
+    block: Option<Block<'a>>,
+    /// The decay times to list, one row each.
+    rows: &'a [DecayTimes],
+}
+
+impl<'a> DecayTable<'a> {
+    /// Creates a new `DecayTable` listing the given decay times.
+    pub fn new(rows: &'a [DecayTimes]) -> Self {
+        DecayTable { block: None, rows }
+    }
+
+    /// Surrounds the `DecayTable` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    fn format_time(time: Option<f32>) -> String {
+        time.map_or_else(|| "-".to_string(), |t| format!("{:.2}s", t))
+    }
+
+    fn format_band(frequency: Option<u16>) -> String {
+        match frequency {
+            Some(f) if f >= 1000 => format!("{}k", f / 1000),
+            Some(f) => f.to_string(),
+            None => "Broad".to_string(),
+        }
+    }
+}
+
+impl Widget for DecayTable<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let header = Row::new(["Band", "EDT", "T20", "T30"]).style(Style::new().bold());
+        let rows = self.rows.iter().map(|times| {
+            Row::new([
+                Self::format_band(times.frequency),
+                Self::format_time(times.edt),
+                Self::format_time(times.t20),
+                Self::format_time(times.t30),
+            ])
+        });
+        let mut table = Table::new(rows, [Constraint::Length(6); 4])
+            .header(header)
+            .style(Style::new().fg(Color::White));
+        if let Some(block) = self.block {
+            table = table.block(block);
+        }
+        table.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 8000.0;
+
+    /// Uniform white noise between -1 and 1 from a fixed seed.
+    fn white_noise(seed: u32) -> impl Iterator<Item = f32> {
+        let mut state = seed;
+        std::iter::repeat_with(move || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+        })
+    }
+
+    /// Exponentially decaying noise with the given RT60, plus steady noise at `noise_db` relative
+    /// to the start of the decay.
+    fn impulse_response(rt60: f32, noise_db: Option<f32>) -> Vec<f32> {
+        let noise_gain = noise_db.map_or(0.0, |db| 10_f32.powf(db / 20.0));
+        white_noise(1)
+            .zip(white_noise(2))
+            .take((2.0 * SAMPLE_RATE) as usize)
+            .enumerate()
+            .map(|(n, (decay, noise))| {
+                let t = n as f32 / SAMPLE_RATE;
+                decay * 10_f32.powf(-3.0 * t / rt60) + noise * noise_gain
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.expect("decay time");
+        assert!(
+            (actual - expected).abs() < 0.05 * expected,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn schroeder_curve_of_exponential_decay_is_linear() {
+        let curve = schroeder_curve(&impulse_response(0.5, None));
+        assert_eq!(curve[0], 0.0);
+        assert!(curve.windows(2).all(|pair| pair[1] <= pair[0]));
+        // 60 dB per 0.5 s is 12 dB per 0.1 s.
+        let step = (0.1 * SAMPLE_RATE) as usize;
+        assert!((curve[2 * step] - curve[step] + 12.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn decay_times_of_clean_decay() {
+        let times = DecayTimes::from_impulse_response(&impulse_response(0.5, None), SAMPLE_RATE);
+        assert_close(times.edt, 0.5);
+        assert_close(times.t20, 0.5);
+        assert_close(times.t30, 0.5);
+    }
+
+    #[test]
+    fn noise_floor_is_truncated() {
+        let response = impulse_response(0.5, Some(-50.0));
+        let times = DecayTimes::from_impulse_response(&response, SAMPLE_RATE);
+        assert_close(times.t20, 0.5);
+        assert_close(times.t30, 0.5);
+
+        // Without truncation the noise flattens the end of the decay curve.
+        let untruncated = decay_time(&schroeder_curve(&response), SAMPLE_RATE, -5.0, -35.0);
+        assert!(untruncated.unwrap() > 0.6);
+    }
+
+    #[test]
+    fn decay_too_close_to_noise_floor_is_none() {
+        let response = impulse_response(0.5, Some(-35.0));
+        let times = DecayTimes::from_impulse_response(&response, SAMPLE_RATE);
+        assert_close(times.edt, 0.5);
+        assert_eq!(times.t30, None);
+    }
+}
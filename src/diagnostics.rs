@@ -0,0 +1,186 @@
+use ratatui::{
+    prelude::{Buffer, Rect, Widget},
+    style::{Color, Stylize},
+    text::{Line, Span},
+};
+
+/// Thresholds used when diagnosing a stereo signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiagnosticThresholds {
+    /// The RMS level in dBFS above which a channel counts as carrying signal.
+    pub presence_db: f32,
+    /// The correlation below which the channels are likely to have opposite polarity.
+    pub flip_correlation: f32,
+    /// The level difference in dB above which the channels count as imbalanced.
+    pub imbalance_db: f32,
+}
+
+impl Default for DiagnosticThresholds {
+    fn default() -> Self {
+        DiagnosticThresholds {
+            presence_db: -60.0,
+            flip_correlation: -0.5,
+            imbalance_db: 3.0,
+        }
+    }
+}
+
+/// Simple diagnostics of a stereo signal, useful for broadcast monitoring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelDiagnostics {
+    /// Whether the left channel carries signal.
+    pub left_present: bool,
+    /// Whether the right channel carries signal.
+    pub right_present: bool,
+    /// The correlation between the channels, from -1.0 (opposite polarity) to 1.0 (identical).
+    pub correlation: f32,
+    /// The level of the left channel relative to the right channel in dB.
+    pub imbalance_db: f32,
+    /// The thresholds the diagnostics were made with.
+    pub thresholds: DiagnosticThresholds,
+}
+
+impl ChannelDiagnostics {
+    /// Diagnoses a block of stereo samples using the default thresholds.
+    pub fn analyze(left: &[f32], right: &[f32]) -> Self {
+        Self::analyze_with(left, right, DiagnosticThresholds::default())
+    }
+
+    /// Diagnoses a block of stereo samples using the given thresholds.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn analyze_with(left: &[f32], right: &[f32], thresholds: DiagnosticThresholds) -> Self {
+        let (mut left_energy, mut right_energy, mut cross) = (0.0_f64, 0.0_f64, 0.0_f64);
+        for (&l, &r) in left.iter().zip(right) {
+            left_energy += (l as f64).powi(2);
+            right_energy += (r as f64).powi(2);
+            cross += l as f64 * r as f64;
+        }
+        let n = left.len().min(right.len()).max(1) as f64;
+        let rms_db = |energy: f64| (10.0 * (energy / n).max(1e-20).log10()) as f32;
+        let (left_db, right_db) = (rms_db(left_energy), rms_db(right_energy));
+
+        let correlation = if left_energy > 0.0 && right_energy > 0.0 {
+            (cross / (left_energy * right_energy).sqrt()) as f32
+        } else {
+            0.0
+        };
+
+        ChannelDiagnostics {
+            left_present: left_db > thresholds.presence_db,
+            right_present: right_db > thresholds.presence_db,
+            correlation,
+            imbalance_db: left_db - right_db,
+            thresholds,
+        }
+    }
+
+    /// Whether the channels are likely to have opposite polarity.
+    pub fn polarity_flipped(&self) -> bool {
+        self.left_present
+            && self.right_present
+            && self.correlation < self.thresholds.flip_correlation
+    }
+
+    /// Whether one channel is considerably louder than the other.
+    pub fn imbalanced(&self) -> bool {
+        self.left_present
+            && self.right_present
+            && self.imbalance_db.abs() > self.thresholds.imbalance_db
+    }
+}
+
+/// A single-line status strip showing [`ChannelDiagnostics`] as icons.
+///
+/// Shows signal presence per channel, a warning when the polarity looks flipped, and the level
+/// difference when the channels are imbalanced.
+#[derive(Debug, Clone)]
+pub struct DiagnosticsStrip<'a> {
+    diagnostics: &'a ChannelDiagnostics,
+}
+
+impl<'a> DiagnosticsStrip<'a> {
+    /// Creates a new `DiagnosticsStrip` showing `diagnostics`.
+    pub fn new(diagnostics: &'a ChannelDiagnostics) -> Self {
+        DiagnosticsStrip { diagnostics }
+    }
+
+    fn presence(label: &'static str, present: bool) -> [Span<'static>; 2] {
+        if present {
+            [Span::raw(label), Span::raw("● ").fg(Color::Green)]
+        } else {
+            [Span::raw(label), Span::raw("○ ").fg(Color::DarkGray)]
+        }
+    }
+}
+
+impl Widget for DiagnosticsStrip<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let d = self.diagnostics;
+        let mut spans = Vec::new();
+        spans.extend(Self::presence("L", d.left_present));
+        spans.extend(Self::presence("R", d.right_present));
+
+        if d.polarity_flipped() {
+            spans.push(Span::raw("Ø⚠ ").fg(Color::Red).bold());
+        } else {
+            spans.push(Span::raw("Ø✓ ").fg(Color::Green));
+        }
+
+        if d.imbalanced() {
+            let louder = if d.imbalance_db > 0.0 { "L" } else { "R" };
+            spans.push(
+                Span::raw(format!("⚖{}+{:.1}dB", louder, d.imbalance_db.abs())).fg(Color::Yellow),
+            );
+        } else {
+            spans.push(Span::raw("⚖✓").fg(Color::Green));
+        }
+
+        Line::from(spans).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    fn sine(amplitude: f32) -> Vec<f32> {
+        (0..480)
+            .map(|n| amplitude * (2.0 * PI * n as f32 / 48.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn identical_channels_are_correlated() {
+        let diagnostics = ChannelDiagnostics::analyze(&sine(0.5), &sine(0.5));
+        assert!((diagnostics.correlation - 1.0).abs() < 1e-6);
+        assert!(diagnostics.imbalance_db.abs() < 1e-6);
+        assert!(!diagnostics.polarity_flipped());
+        assert!(!diagnostics.imbalanced());
+    }
+
+    #[test]
+    fn detects_flipped_polarity() {
+        let diagnostics = ChannelDiagnostics::analyze(&sine(0.5), &sine(-0.5));
+        assert!((diagnostics.correlation - -1.0).abs() < 1e-6);
+        assert!(diagnostics.polarity_flipped());
+    }
+
+    #[test]
+    fn detects_imbalance() {
+        // Half the amplitude is 6.02 dB quieter.
+        let diagnostics = ChannelDiagnostics::analyze(&sine(0.5), &sine(0.25));
+        assert!((diagnostics.imbalance_db - 6.02).abs() < 0.01);
+        assert!(diagnostics.imbalanced());
+    }
+
+    #[test]
+    fn silent_channel_is_absent() {
+        let diagnostics = ChannelDiagnostics::analyze(&sine(0.5), &[0.0; 480]);
+        assert!(diagnostics.left_present);
+        assert!(!diagnostics.right_present);
+        assert_eq!(diagnostics.correlation, 0.0);
+        assert!(!diagnostics.imbalanced());
+    }
+}
@@ -0,0 +1,267 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{rta::Band, trace::FrequencyPoint};
+
+/// The WAVE format tag for IEEE floating point samples.
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// Writes an impulse response as a mono 32-bit float WAV file.
+pub fn write_impulse_response_wav(
+    path: impl AsRef<Path>,
+    samples: &[f32],
+    sample_rate: u32,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_wav(&mut writer, samples, sample_rate)?;
+    writer.flush()
+}
+
+/// Writes mono 32-bit float WAV data to `writer`.
+pub fn write_wav(mut writer: impl Write, samples: &[f32], sample_rate: u32) -> io::Result<()> {
+    const BYTES_PER_SAMPLE: u32 = 4;
+    // "WAVE" + fmt chunk (8 + 18) + fact chunk (8 + 4) + data chunk header (8).
+    const HEADER_LEN: u32 = 4 + 26 + 12 + 8;
+    let data_len = u32::try_from(samples.len())
+        .ok()
+        .and_then(|len| len.checked_mul(BYTES_PER_SAMPLE));
+    let riff_len = data_len.and_then(|len| len.checked_add(HEADER_LEN));
+    let (Some(data_len), Some(riff_len)) = (data_len, riff_len) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "too many samples for WAV",
+        ));
+    };
+    let byte_rate = sample_rate.checked_mul(BYTES_PER_SAMPLE).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "sample rate too high for WAV")
+    })?;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&riff_len.to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&18_u32.to_le_bytes())?;
+    writer.write_all(&WAVE_FORMAT_IEEE_FLOAT.to_le_bytes())?;
+    writer.write_all(&1_u16.to_le_bytes())?; // channels
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&byte_rate.to_le_bytes())?;
+    writer.write_all(&(BYTES_PER_SAMPLE as u16).to_le_bytes())?; // block align
+    writer.write_all(&32_u16.to_le_bytes())?; // bits per sample
+    writer.write_all(&0_u16.to_le_bytes())?; // extension size
+
+    // Non-PCM formats require a fact chunk holding the number of samples.
+    writer.write_all(b"fact")?;
+    writer.write_all(&4_u32.to_le_bytes())?;
+    writer.write_all(&(samples.len() as u32).to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())?;
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Writes a frequency response as a REW compatible text file.
+///
+/// Each line holds the frequency in Hz, the magnitude in dB and, when known, the phase in degrees,
+/// separated by spaces. Header lines start with `*`, which REW treats as comments.
+pub fn write_rew_text(path: impl AsRef<Path>, points: &[FrequencyPoint]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_rew(&mut writer, points)?;
+    writer.flush()
+}
+
+/// Writes a frequency response in REW text format to `writer`.
+pub fn write_rew(mut writer: impl Write, points: &[FrequencyPoint]) -> io::Result<()> {
+    writeln!(writer, "* Exported by rataudio-rta")?;
+    writeln!(writer, "* Freq(Hz) SPL(dB) Phase(degrees)")?;
+    for point in points {
+        match point.phase_deg {
+            Some(phase) => writeln!(
+                writer,
+                "{:.6} {:.3} {:.3}",
+                point.frequency, point.magnitude_db, phase
+            )?,
+            None => writeln!(writer, "{:.6} {:.3}", point.frequency, point.magnitude_db)?,
+        }
+    }
+    Ok(())
+}
+
+/// The character between the columns of a text table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delimiter {
+    /// Tab separated values, which spreadsheets split into columns when pasted.
+    #[default]
+    Tab,
+    /// Comma separated values.
+    Comma,
+}
+
+/// Formats the current levels of `bands` as a text table with a header row, one band per row, for
+/// pasting into a spreadsheet.
+///
+/// Bands without a frequency have an empty frequency column, and bands with a NaN value an empty
+/// level column.
+///
+/// ```
+/// use rataudio_rta::{Band, Delimiter, format_spectrum};
+///
+/// let text = format_spectrum(&[Band::new(0.5, 1000)], -60.0, Delimiter::Comma);
+/// assert_eq!(text, "Frequency (Hz),Level (dB)\n1000,-30.00\n");
+/// ```
+pub fn format_spectrum(bands: &[Band], min_db: f32, delimiter: Delimiter) -> String {
+    let delimiter = match delimiter {
+        Delimiter::Tab => '\t',
+        Delimiter::Comma => ',',
+    };
+    let mut text = format!("Frequency (Hz){delimiter}Level (dB)\n");
+    for band in bands {
+        let frequency = band.frequency.map(|f| f.to_string()).unwrap_or_default();
+        let level = if band.value.is_nan() {
+            String::new()
+        } else {
+            format!("{:.2}", band.get_db(min_db))
+        };
+        text.push_str(&format!("{frequency}{delimiter}{level}\n"));
+    }
+    text
+}
+
+/// Copies `text` to the system clipboard by writing an OSC 52 escape sequence to `writer`, which
+/// should be the terminal, e.g. [`std::io::stdout`].
+///
+/// The terminal sets the clipboard, so this also works over SSH, but only in terminals that support
+/// OSC 52 and allow it. Nothing is reported when the terminal ignores the sequence.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(mut writer: impl Write, text: &str) -> io::Result<()> {
+    write!(writer, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    writer.flush()
+}
+
+/// Encodes `bytes` as standard base64 with padding.
+#[cfg(feature = "clipboard")]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0_u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_float_wav() {
+        let mut wav = Vec::new();
+        write_wav(&mut wav, &[0.5, -1.0], 48000).unwrap();
+        #[rustfmt::skip]
+        let expected: [u8; 66] = [
+            b'R', b'I', b'F', b'F', 58, 0, 0, 0, b'W', b'A', b'V', b'E',
+            b'f', b'm', b't', b' ', 18, 0, 0, 0,
+            3, 0, // IEEE float
+            1, 0, // channels
+            0x80, 0xbb, 0, 0, // 48000 Hz
+            0x00, 0xee, 0x02, 0, // 192000 bytes per second
+            4, 0, // block align
+            32, 0, // bits per sample
+            0, 0, // extension size
+            b'f', b'a', b'c', b't', 4, 0, 0, 0, 2, 0, 0, 0,
+            b'd', b'a', b't', b'a', 8, 0, 0, 0,
+            0, 0, 0, 0x3f, // 0.5
+            0, 0, 0x80, 0xbf, // -1.0
+        ];
+        assert_eq!(wav, expected);
+    }
+
+    #[test]
+    fn rew_text_round_trips_through_import() {
+        let points = vec![
+            FrequencyPoint::new(20.0, -3.5),
+            FrequencyPoint::new(1000.0, 0.0).with_phase(-45.25),
+            FrequencyPoint::new(20000.0, -12.125).with_phase(179.5),
+        ];
+        let mut text = Vec::new();
+        write_rew(&mut text, &points).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.starts_with("* "));
+        let parsed = crate::import::parse_frequency_response(&text).unwrap();
+        assert_eq!(parsed, points);
+    }
+
+    #[test]
+    fn formats_spectrum_with_delimiter() {
+        let bands = [Band::new(1.0, 100), Band::new(0.25, 1000)];
+        assert_eq!(
+            format_spectrum(&bands, -60.0, Delimiter::Tab),
+            "Frequency (Hz)\tLevel (dB)\n100\t0.00\n1000\t-45.00\n"
+        );
+        assert_eq!(
+            format_spectrum(&bands, -60.0, Delimiter::Comma),
+            "Frequency (Hz),Level (dB)\n100,0.00\n1000,-45.00\n"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_columns_empty() {
+        let bands = [
+            Band {
+                frequency: None,
+                ..Band::new(0.5, 0)
+            },
+            Band::new(f32::NAN, 1000),
+        ];
+        assert_eq!(
+            format_spectrum(&bands, -60.0, Delimiter::Comma),
+            "Frequency (Hz),Level (dB)\n,-30.00\n1000,\n"
+        );
+        assert_eq!(
+            format_spectrum(&[], -60.0, Delimiter::Tab).lines().count(),
+            1
+        );
+    }
+
+    #[cfg(feature = "clipboard")]
+    #[test]
+    fn encodes_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe, 0x00]), "//4A");
+    }
+
+    #[cfg(feature = "clipboard")]
+    #[test]
+    fn copies_with_osc_52() {
+        let mut terminal = Vec::new();
+        copy_to_clipboard(&mut terminal, "1000\t-3.00").unwrap();
+        assert_eq!(terminal, b"\x1b]52;c;MTAwMAktMy4wMA==\x07");
+    }
+
+    #[test]
+    fn rejects_sample_rate_too_high_for_header() {
+        let mut wav = Vec::new();
+        let error = write_wav(&mut wav, &[0.0], u32::MAX / 2).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(wav.is_empty());
+    }
+}
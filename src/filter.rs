@@ -0,0 +1,218 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+/// A second order IIR filter section in transposed direct form II.
+#[derive(Debug, Clone)]
+pub(crate) struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// Creates a high-pass section using the RBJ audio EQ cookbook formulas.
+    pub(crate) fn high_pass(cutoff_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let (cos_w0, alpha) = Self::rbj_params(cutoff_hz, q, sample_rate);
+        Self::normalized(
+            (1.0 + cos_w0) / 2.0,
+            -(1.0 + cos_w0),
+            (1.0 + cos_w0) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos_w0,
+            1.0 - alpha,
+        )
+    }
+
+    /// Creates a band-pass section with 0 dB peak gain using the RBJ audio EQ cookbook formulas.
+    pub(crate) fn band_pass(center_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let (cos_w0, alpha) = Self::rbj_params(center_hz, q, sample_rate);
+        Self::normalized(alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha)
+    }
+
+    /// Creates a section from the coefficients of an analog prototype
+    /// `(b0 s² + b1 s + b2) / (a0 s² + a1 s + a2)` using the bilinear transform.
+    pub(crate) fn bilinear(b: [f64; 3], a: [f64; 3], sample_rate: f32) -> Self {
+        let k = 2.0 * sample_rate as f64;
+        let k2 = k * k;
+        let digital = |c: [f64; 3]| {
+            [
+                c[0] * k2 + c[1] * k + c[2],
+                2.0 * (c[2] - c[0] * k2),
+                c[0] * k2 - c[1] * k + c[2],
+            ]
+        };
+        let [b0, b1, b2] = digital(b);
+        let [a0, a1, a2] = digital(a);
+        Self::normalized(
+            (b0 / a0) as f32,
+            (b1 / a0) as f32,
+            (b2 / a0) as f32,
+            1.0,
+            (a1 / a0) as f32,
+            (a2 / a0) as f32,
+        )
+    }
+
+    /// The magnitude of the frequency response of the section at `frequency`.
+    pub(crate) fn gain_at(&self, frequency: f32, sample_rate: f32) -> f32 {
+        let w = 2.0 * PI * frequency / sample_rate;
+        // Evaluate the numerator and denominator polynomials at z^-1 = e^(-jw).
+        let eval = |c0: f32, c1: f32, c2: f32| {
+            let re = c0 + c1 * w.cos() + c2 * (2.0 * w).cos();
+            let im = -c1 * w.sin() - c2 * (2.0 * w).sin();
+            re.hypot(im)
+        };
+        eval(self.b0, self.b1, self.b2) / eval(1.0, self.a1, self.a2)
+    }
+
+    /// Scales the output of the section by `gain`.
+    pub(crate) fn scale(&mut self, gain: f32) {
+        self.b0 *= gain;
+        self.b1 *= gain;
+        self.b2 *= gain;
+    }
+
+    /// The `cos(w0)` and `alpha` parameters of the RBJ audio EQ cookbook formulas.
+    fn rbj_params(cutoff_hz: f32, q: f32, sample_rate: f32) -> (f32, f32) {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+
+    fn normalized(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Biquad {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub(crate) fn process_sample(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// A second order Butterworth high-pass filter for removing DC offset and subsonic content.
+///
+/// Cheap audio interfaces often add DC offset or rumble that would otherwise dominate the lowest
+/// bands. Run the samples through the filter before analysis; it can be switched off at runtime,
+/// in which case samples pass through untouched.
+#[derive(Debug, Clone)]
+pub struct HighPassFilter {
+    biquad: Biquad,
+    cutoff_hz: f32,
+    enabled: bool,
+}
+
+impl HighPassFilter {
+    /// The default cutoff frequency in Hz.
+    pub const DEFAULT_CUTOFF_HZ: f32 = 10.0;
+
+    /// Creates an enabled high-pass filter with the given cutoff frequency.
+    pub fn new(cutoff_hz: f32, sample_rate: f32) -> Self {
+        HighPassFilter {
+            biquad: Biquad::high_pass(cutoff_hz, FRAC_1_SQRT_2, sample_rate),
+            cutoff_hz,
+            enabled: true,
+        }
+    }
+
+    /// Creates an enabled high-pass filter with a cutoff of 10 Hz.
+    pub fn dc_blocker(sample_rate: f32) -> Self {
+        Self::new(Self::DEFAULT_CUTOFF_HZ, sample_rate)
+    }
+
+    /// The cutoff frequency of the filter in Hz.
+    pub fn cutoff_hz(&self) -> f32 {
+        self.cutoff_hz
+    }
+
+    /// Whether the filter is applied to processed samples.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or bypasses the filter.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            // Don't let stale state from before the bypass leak into the output.
+            self.biquad.reset();
+        }
+        self.enabled = enabled;
+    }
+
+    /// Filters the samples in place.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if !self.enabled {
+            return;
+        }
+        for sample in samples {
+            *sample = self.biquad.process_sample(*sample);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn gain_db(biquad: &Biquad, frequency: f32) -> f32 {
+        20.0 * biquad.gain_at(frequency, SAMPLE_RATE).log10()
+    }
+
+    #[test]
+    fn high_pass_is_3_db_down_at_cutoff() {
+        let biquad = Biquad::high_pass(100.0, FRAC_1_SQRT_2, SAMPLE_RATE);
+        assert!((gain_db(&biquad, 100.0) + 3.01).abs() < 0.05);
+        assert!(gain_db(&biquad, 10_000.0).abs() < 0.01);
+        // 12 dB per octave below the cutoff.
+        assert!((gain_db(&biquad, 12.5) + 36.1).abs() < 0.2);
+    }
+
+    #[test]
+    fn band_pass_peaks_at_center() {
+        let biquad = Biquad::band_pass(1000.0, 2.0, SAMPLE_RATE);
+        assert!(gain_db(&biquad, 1000.0).abs() < 0.01);
+        assert!(gain_db(&biquad, 500.0) < -6.0);
+        assert!(gain_db(&biquad, 2000.0) < -6.0);
+    }
+
+    #[test]
+    fn bilinear_keeps_dc_gain() {
+        // A first order low-pass 1 / (s / w + 1) written as a second order section.
+        let w = 2.0 * std::f64::consts::PI * 100.0;
+        let biquad = Biquad::bilinear([0.0, 0.0, w], [0.0, 1.0, w], SAMPLE_RATE);
+        assert!((biquad.gain_at(0.0, SAMPLE_RATE) - 1.0).abs() < 1e-4);
+        assert!((gain_db(&biquad, 100.0) + 3.01).abs() < 0.05);
+    }
+
+    #[test]
+    fn dc_blocker_removes_offset() {
+        let mut filter = HighPassFilter::dc_blocker(SAMPLE_RATE);
+        let mut samples = vec![0.5; 48000];
+        filter.process(&mut samples);
+        assert!(samples[47999].abs() < 1e-3);
+
+        filter.set_enabled(false);
+        let mut samples = vec![0.5; 10];
+        filter.process(&mut samples);
+        assert_eq!(samples, vec![0.5; 10]);
+    }
+}
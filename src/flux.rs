@@ -0,0 +1,99 @@
+use crate::rta::Band;
+
+/// Measures the spectral flux of successive band snapshots: how much the bands rose in level since
+/// the previous snapshot.
+///
+/// Sudden jumps in flux mark transients, where lossy codecs tend to smear pre-echo ahead of the
+/// attack. Compare the flux with a threshold and call [`crate::RTAState::trigger_alert`] to flag
+/// them on the meter.
+///
+/// ```
+/// use rataudio_rta::{Band, SpectralFlux};
+///
+/// let mut flux = SpectralFlux::new(-60.0);
+/// assert_eq!(flux.push(&[Band::new(0.5, 1000)]), 0.0);
+/// assert!((flux.push(&[Band::new(1.0, 1000)]) - 30.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpectralFlux {
+    /// The lowest value of the dB scale the bands are measured on.
+    min_db: f32,
+    /// The level of each band in dB in the previous snapshot.
+    previous: Vec<f32>,
+}
+
+impl SpectralFlux {
+    /// Creates a new `SpectralFlux` for bands on a scale starting at `min_db`.
+    pub fn new(min_db: f32) -> Self {
+        SpectralFlux {
+            min_db,
+            previous: Vec::new(),
+        }
+    }
+
+    /// Adds a snapshot of the band values and returns the mean rise in dB per band since the
+    /// previous snapshot. Bands that fell in level count as zero.
+    ///
+    /// Returns 0 for the first snapshot, after a reset and when the number of bands changed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn push(&mut self, bands: &[Band]) -> f32 {
+        let levels: Vec<f32> = bands
+            .iter()
+            .map(|band| {
+                if band.value.is_nan() {
+                    self.min_db
+                } else {
+                    band.get_db(self.min_db)
+                }
+            })
+            .collect();
+        let flux = if levels.len() == self.previous.len() && !levels.is_empty() {
+            let rise: f32 = levels
+                .iter()
+                .zip(&self.previous)
+                .map(|(db, previous)| (db - previous).max(0.0))
+                .sum();
+            rise / levels.len() as f32
+        } else {
+            0.0
+        };
+        self.previous = levels;
+        flux
+    }
+
+    /// Forgets the previous snapshot.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_rising_bands() {
+        let mut flux = SpectralFlux::new(-60.0);
+        flux.push(&[Band::new(0.5, 100), Band::new(0.5, 1000)]);
+        // One band rises by 12 dB and the other falls, so the mean rise is 6 dB.
+        let rise = flux.push(&[Band::new(0.7, 100), Band::new(0.1, 1000)]);
+        assert!((rise - 6.0).abs() < 1e-3, "{rise}");
+        assert_eq!(flux.push(&[Band::new(0.7, 100), Band::new(0.1, 1000)]), 0.0);
+    }
+
+    #[test]
+    fn nan_band_counts_as_floor() {
+        let mut flux = SpectralFlux::new(-60.0);
+        flux.push(&[Band::new(f32::NAN, 1000)]);
+        assert!((flux.push(&[Band::new(0.5, 1000)]) - 30.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn restarts_after_reset_or_band_change() {
+        let mut flux = SpectralFlux::new(-60.0);
+        flux.push(&[Band::new(0.0, 1000)]);
+        flux.reset();
+        assert_eq!(flux.push(&[Band::new(1.0, 1000)]), 0.0);
+        assert_eq!(flux.push(&[Band::new(1.0, 100), Band::new(1.0, 1000)]), 0.0);
+    }
+}
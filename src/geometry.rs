@@ -0,0 +1,164 @@
+use ratatui::layout::Rect;
+
+use crate::rta::{Band, DisplayScale};
+
+/// Where the bars of an `RTA` are drawn, and how frequencies and levels map to cells.
+///
+/// Get it for an area with [`crate::RTA::geometry`], from the last render with
+/// [`crate::RTAState::geometry`], or in an [`crate::Overlay`]. Use it to align other widgets,
+/// overlays and mouse handling exactly with the meter.
+///
+/// ```
+/// use ratatui::layout::Rect;
+/// use rataudio_rta::{Band, RTA};
+///
+/// let rta = RTA::new(vec![Band::new(0.5, 100), Band::new(0.5, 1000)], -60.0);
+/// let geometry = rta.geometry(Rect::new(0, 0, 40, 12)).unwrap();
+/// let column = geometry.column_of(1000.0).unwrap();
+/// assert_eq!(geometry.band_at(column), Some(1));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtaGeometry {
+    /// The area of each band.
+    band_areas: Vec<Rect>,
+    /// The area covered by the bars.
+    bars_area: Rect,
+    /// The width of each bar.
+    bar_width: u16,
+    /// The level in dB at the bottom of the meter.
+    min_db: f32,
+    /// How levels map to the bar height.
+    display_scale: DisplayScale,
+    /// The center frequencies of the bands, if there are at least two and all have one.
+    frequencies: Option<Vec<f32>>,
+}
+
+impl RtaGeometry {
+    pub(crate) fn new(
+        bands: &[Band],
+        band_areas: Vec<Rect>,
+        bars_area: Rect,
+        bar_width: u16,
+        min_db: f32,
+        display_scale: DisplayScale,
+    ) -> Self {
+        let frequencies = bands
+            .iter()
+            .map(|band| band.frequency.filter(|&f| f > 0).map(f32::from))
+            .collect::<Option<Vec<_>>>()
+            .filter(|frequencies| frequencies.len() >= 2);
+        RtaGeometry {
+            band_areas,
+            bars_area,
+            bar_width,
+            min_db,
+            display_scale,
+            frequencies,
+        }
+    }
+
+    /// The area of each band. Bands that don't fit have an empty area.
+    pub fn band_areas(&self) -> &[Rect] {
+        &self.band_areas
+    }
+
+    /// The area covered by the bars.
+    pub fn bars_area(&self) -> Rect {
+        self.bars_area
+    }
+
+    /// The width of each bar.
+    pub fn bar_width(&self) -> u16 {
+        self.bar_width
+    }
+
+    /// The level in dB at the bottom of the meter.
+    pub fn min_db(&self) -> f32 {
+        self.min_db
+    }
+
+    /// The index of the band drawn in `column`, if any.
+    pub fn band_at(&self, column: u16) -> Option<usize> {
+        self.band_areas
+            .iter()
+            .position(|area| (area.left()..area.right()).contains(&column))
+    }
+
+    /// The row showing `db`, or `None` if it is below the bottom of the meter.
+    ///
+    /// Levels above the top of the meter are shown in the top row.
+    pub fn row_of(&self, db: f32) -> Option<u16> {
+        let area = self.bars_area;
+        if area.is_empty() || db.is_nan() || db < self.min_db {
+            return None;
+        }
+        let value = self.display_scale.ratio(db, self.min_db);
+        let row = ((value * area.height as f32) as u16).min(area.height - 1);
+        Some(area.bottom() - 1 - row)
+    }
+
+    /// The level in dB at the middle of `row`, or `None` if it is outside the bars.
+    ///
+    /// Using the middle of the row makes the level map back onto the same row.
+    pub fn db_at(&self, row: u16) -> Option<f32> {
+        let area = self.bars_area;
+        if row < area.top() || row >= area.bottom() {
+            return None;
+        }
+        let value = ((area.bottom() - 1 - row) as f32 + 0.5) / area.height as f32;
+        Some(self.display_scale.db(value, self.min_db))
+    }
+
+    /// The column showing `frequency` in Hz, or `None` if it is outside the bars or the bands
+    /// have no frequencies.
+    pub fn column_of(&self, frequency: f32) -> Option<u16> {
+        let position = self.band_position(frequency)?;
+        let offset = ((position + 0.5) * self.bar_width as f32).floor();
+        let area = self.bars_area;
+        (0.0..area.width as f32)
+            .contains(&offset)
+            .then(|| area.x + offset as u16)
+    }
+
+    /// The frequency in Hz at the middle of `column`, or `None` if it is outside the bars or the
+    /// bands have no frequencies.
+    pub fn frequency_at(&self, column: u16) -> Option<f32> {
+        let area = self.bars_area;
+        if column < area.left() || column >= area.right() {
+            return None;
+        }
+        self.frequency_at_offset((column - area.x) as f32 + 0.5)
+    }
+
+    /// The frequency in Hz at a fractional number of cells from the left edge of the bars,
+    /// extrapolated beyond the first and last band.
+    pub(crate) fn frequency_at_offset(&self, offset: f32) -> Option<f32> {
+        self.frequency_at_position(offset / self.bar_width as f32 - 0.5)
+    }
+
+    /// The frequency at a fractional band index, interpolated logarithmically between the center
+    /// frequencies of the bands and extrapolated beyond the first and last band.
+    fn frequency_at_position(&self, position: f32) -> Option<f32> {
+        let frequencies = self.frequencies.as_deref()?;
+        let i = (position.floor().max(0.0) as usize).min(frequencies.len() - 2);
+        let (low, high) = (frequencies[i].log2(), frequencies[i + 1].log2());
+        Some(2_f32.powf(low + (high - low) * (position - i as f32)))
+    }
+
+    /// The fractional band index of `frequency`, the inverse of `frequency_at_position`.
+    fn band_position(&self, frequency: f32) -> Option<f32> {
+        let frequencies = self.frequencies.as_deref()?;
+        if frequency.is_nan() || frequency <= 0.0 {
+            return None;
+        }
+        let i = frequencies
+            .windows(2)
+            .position(|pair| frequency < pair[1])
+            .unwrap_or(frequencies.len() - 2);
+        let (low, high) = (frequencies[i].log2(), frequencies[i + 1].log2());
+        if high == low {
+            return Some(i as f32);
+        }
+        Some(i as f32 + (frequency.log2() - low) / (high - low))
+    }
+}
@@ -0,0 +1,124 @@
+use std::{fs, io, path::Path};
+
+use crate::trace::FrequencyPoint;
+
+/// Reads a frequency response from a REW or ARTA text export.
+///
+/// See [`parse_frequency_response`] for the accepted format.
+pub fn read_frequency_response(path: impl AsRef<Path>) -> io::Result<Vec<FrequencyPoint>> {
+    parse_frequency_response(&fs::read_to_string(path)?)
+}
+
+/// Parses a frequency response from the text of a REW or ARTA export.
+///
+/// Each data line holds a frequency in Hz, a magnitude in dB and optionally a phase in degrees,
+/// separated by whitespace, commas or semicolons. Lines that don't start with a number, such as
+/// comments and column headers, are skipped. The points are returned sorted by frequency.
+///
+/// Exports from locales with a decimal comma, e.g. `1000,0;-3,5` or `1000,0<TAB>-3,5`, are
+/// detected per file: if any data line contains a semicolon, or the data has no decimal points
+/// but whitespace-separated numbers with commas, commas are read as decimal separators.
+pub fn parse_frequency_response(content: &str) -> io::Result<Vec<FrequencyPoint>> {
+    let decimal_comma = has_decimal_comma(content);
+    let mut points: Vec<FrequencyPoint> = content
+        .lines()
+        .filter_map(|line| parse_line(line, decimal_comma))
+        .collect();
+    if points.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no frequency response data found",
+        ));
+    }
+    points.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+    Ok(points)
+}
+
+/// Whether the numbers in the data lines of `content` use a comma as decimal separator.
+fn has_decimal_comma(content: &str) -> bool {
+    let mut decimal_point = false;
+    let mut spaced_commas = false;
+    let data_lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with(|c: char| c.is_ascii_digit()));
+    for line in data_lines {
+        if line.contains(';') {
+            return true;
+        }
+        decimal_point |= line.contains('.');
+        spaced_commas |= line
+            .split_whitespace()
+            .filter(|token| token.contains(',') && !token.ends_with(','))
+            .count()
+            >= 2;
+    }
+    spaced_commas && !decimal_point
+}
+
+fn parse_line(line: &str, decimal_comma: bool) -> Option<FrequencyPoint> {
+    let mut values = line
+        .split(|c: char| c.is_whitespace() || c == ';' || (c == ',' && !decimal_comma))
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            if decimal_comma {
+                token.replace(',', ".").parse::<f32>()
+            } else {
+                token.parse::<f32>()
+            }
+        });
+
+    let frequency = values.next()?.ok()?;
+    let magnitude_db = values.next()?.ok()?;
+    if !frequency.is_finite() || frequency <= 0.0 || !magnitude_db.is_finite() {
+        return None;
+    }
+    let point = FrequencyPoint::new(frequency, magnitude_db);
+    match values.next() {
+        Some(Ok(phase)) if phase.is_finite() => Some(point.with_phase(phase)),
+        _ => Some(point),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Vec<(f32, f32, Option<f32>)> {
+        parse_frequency_response(content)
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.frequency, p.magnitude_db, p.phase_deg))
+            .collect()
+    }
+
+    #[test]
+    fn parses_rew_export() {
+        let content = "* Measurement data\n* Freq(Hz) SPL(dB) Phase(degrees)\n\
+                       1000.0 -3.5 12.0\n20.0 -10.25 -90.0\n";
+        assert_eq!(
+            parse(content),
+            [(20.0, -10.25, Some(-90.0)), (1000.0, -3.5, Some(12.0))]
+        );
+    }
+
+    #[test]
+    fn parses_comma_separated_values() {
+        assert_eq!(
+            parse("Freq,dB\n20,-3\n1000, -3.5\n"),
+            [(20.0, -3.0, None), (1000.0, -3.5, None)]
+        );
+    }
+
+    #[test]
+    fn parses_decimal_comma() {
+        let expected = [(20.5, -10.0, None), (1000.0, -3.5, None)];
+        assert_eq!(parse("Freq;dB\n20,5;-10\n1000,0;-3,5\n"), expected);
+        assert_eq!(parse("20,5\t-10\n1000,0\t-3,5\n"), expected);
+    }
+
+    #[test]
+    fn rejects_file_without_data() {
+        assert!(parse_frequency_response("* no data\n").is_err());
+    }
+}
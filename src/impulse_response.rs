@@ -0,0 +1,468 @@
+use std::{borrow::Cow, f32::consts::PI, ops::Range};
+
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::Color,
+    symbols::Marker,
+    widgets::{
+        Block, Paragraph,
+        canvas::{Canvas, Line},
+    },
+};
+
+/// The amplitude scale used to plot an impulse response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmplitudeScale {
+    /// Signed amplitude, normalized to the absolute peak of the response.
+    #[default]
+    Linear,
+    /// Magnitude in dB relative to the absolute peak of the response.
+    Log,
+    /// Energy-time curve in dB relative to the peak energy, see [`energy_time_curve`].
+    ///
+    /// Pass the curve to [`ImpulseResponse::energy_curve`] to avoid computing it on every render.
+    Energy,
+}
+
+/// The speed of sound in air at 20 °C, in meters per second.
+pub const SPEED_OF_SOUND: f32 = 343.0;
+
+/// The number of taps of the FIR Hilbert transformer used for the energy-time curve.
+const HILBERT_TAPS: usize = 63;
+
+/// Computes the energy-time curve of an impulse response in dB relative to its peak energy.
+///
+/// The energy is the squared envelope of the analytic signal, so the curve shows the arrival of
+/// reflections without the zero crossings of the raw response.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn energy_time_curve(samples: &[f32]) -> Vec<f32> {
+    let half = HILBERT_TAPS / 2;
+    let taps: Vec<f32> = (0..HILBERT_TAPS)
+        .map(|k| {
+            let n = k as f32 - half as f32;
+            if (k + half).is_multiple_of(2) {
+                return 0.0;
+            }
+            // Hamming windowed ideal Hilbert transformer.
+            let window = 0.54 + 0.46 * (PI * n / half as f32).cos();
+            2.0 / (PI * n) * window
+        })
+        .collect();
+
+    // Tap `k` has the offset `k - half`, so it weighs the sample at `i + half - k`.
+    let energy: Vec<f32> = (0..samples.len())
+        .map(|i| {
+            let quadrature: f32 = taps
+                .iter()
+                .enumerate()
+                .filter_map(|(k, tap)| {
+                    let j = (i + half).checked_sub(k)?;
+                    samples.get(j).map(|x| tap * x)
+                })
+                .sum();
+            samples[i].powi(2) + quadrature.powi(2)
+        })
+        .collect();
+
+    let peak = energy.iter().fold(0.0_f32, |max, &e| max.max(e));
+    energy
+        .iter()
+        .map(|&e| (10.0 * (e / peak).log10()).max(-200.0))
+        .collect()
+}
+
+/// A time window over an impulse response, used to gate which part of it is analyzed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeWindow {
+    /// The start of the window in milliseconds.
+    pub start_ms: f32,
+    /// The end of the window in milliseconds.
+    pub end_ms: f32,
+}
+
+impl TimeWindow {
+    /// Creates a new window between `start_ms` and `end_ms`.
+    pub fn new(start_ms: f32, end_ms: f32) -> Self {
+        TimeWindow {
+            start_ms: start_ms.min(end_ms).max(0.0),
+            end_ms: start_ms.max(end_ms).max(0.0),
+        }
+    }
+
+    /// The length of the window in milliseconds.
+    pub fn length_ms(&self) -> f32 {
+        self.end_ms - self.start_ms
+    }
+
+    /// Moves the window by `delta_ms`, keeping its length. The window never starts before 0 ms.
+    pub fn shift(&mut self, delta_ms: f32) {
+        let length = self.length_ms();
+        self.start_ms = (self.start_ms + delta_ms).max(0.0);
+        self.end_ms = self.start_ms + length;
+    }
+
+    /// The range of sample indices covered by the window, limited to `len` samples.
+    ///
+    /// A window whose end was moved before its start covers the samples between the two.
+    pub fn sample_range(&self, sample_rate: f32, len: usize) -> Range<usize> {
+        let to_index = |ms: f32| ((ms / 1000.0 * sample_rate).round() as usize).min(len);
+        let (start, end) = (to_index(self.start_ms), to_index(self.end_ms));
+        start.min(end)..start.max(end)
+    }
+
+    /// Returns the part of the impulse response covered by the window.
+    pub fn apply<'a>(&self, samples: &'a [f32], sample_rate: f32) -> &'a [f32] {
+        &samples[self.sample_range(sample_rate, samples.len())]
+    }
+}
+
+/// A widget plotting a measured impulse response against time in milliseconds.
+///
+/// An optional [`TimeWindow`] is drawn on top of the response, so users can see and move the part
+/// of the response used to gate further analysis.
+#[derive(Debug, Clone)]
+pub struct ImpulseResponse<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The impulse response samples.
+    samples: &'a [f32],
+    /// The sample rate of the impulse response.
+    sample_rate: f32,
+    /// The amplitude scale of the plot.
+    scale: AmplitudeScale,
+    /// The lowest value of the plot when using a dB scale.
+    min_db: f32,
+    /// The gating window, if any.
+    window: Option<TimeWindow>,
+    /// The color of the response trace.
+    color: Color,
+    /// The color of the window edges.
+    window_color: Color,
+    /// The position of the cursor in milliseconds, if any.
+    cursor_ms: Option<f32>,
+    /// The precomputed energy-time curve of the samples, if any.
+    energy_curve: Option<&'a [f32]>,
+}
+
+impl<'a> ImpulseResponse<'a> {
+    /// Creates a new `ImpulseResponse` widget plotting `samples` recorded at `sample_rate`.
+    pub fn new(samples: &'a [f32], sample_rate: f32) -> Self {
+        ImpulseResponse {
+            block: None,
+            samples,
+            sample_rate,
+            scale: AmplitudeScale::default(),
+            min_db: -60.0,
+            window: None,
+            color: Color::Yellow,
+            window_color: Color::Cyan,
+            cursor_ms: None,
+            energy_curve: None,
+        }
+    }
+
+    /// Sets the amplitude scale of the plot.
+    pub fn scale(mut self, scale: AmplitudeScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Plots `curve` with [`AmplitudeScale::Energy`] instead of computing it on every render.
+    ///
+    /// The curve should be computed with [`energy_time_curve`] once, when the response is set.
+    pub fn energy_curve(mut self, curve: &'a [f32]) -> Self {
+        self.energy_curve = Some(curve);
+        self
+    }
+
+    /// Sets the lowest value shown when using [`AmplitudeScale::Log`] or [`AmplitudeScale::Energy`].
+    pub fn min_db(mut self, min_db: f32) -> Self {
+        self.min_db = min_db;
+        self
+    }
+
+    /// Draws the given gating window on top of the response.
+    pub fn window(mut self, window: TimeWindow) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Sets the color of the response trace.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the color of the window edges.
+    pub fn window_color(mut self, color: Color) -> Self {
+        self.window_color = color;
+        self
+    }
+
+    /// Places a cursor at `ms`, labelled with its delay after the peak of the response and the
+    /// corresponding distance traveled by sound.
+    pub fn cursor(mut self, ms: f32) -> Self {
+        self.cursor_ms = Some(ms);
+        self
+    }
+
+    /// Surrounds the `ImpulseResponse` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    fn duration_ms(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate * 1000.0
+    }
+
+    /// The time of the absolute peak of the response, i.e. the arrival of the direct sound.
+    fn peak_ms(&self) -> f32 {
+        let peak_index = self
+            .samples
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                a.abs()
+                    .partial_cmp(&b.abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map_or(0, |(i, _)| i);
+        peak_index as f32 / self.sample_rate * 1000.0
+    }
+
+    fn y_bounds(&self) -> [f64; 2] {
+        match self.scale {
+            AmplitudeScale::Linear => [-1.0, 1.0],
+            AmplitudeScale::Log | AmplitudeScale::Energy => [self.min_db as f64, 0.0],
+        }
+    }
+
+    /// The values that are plotted, which are the raw samples except for the energy-time curve.
+    fn plotted_values(&self) -> Cow<'a, [f32]> {
+        match self.scale {
+            AmplitudeScale::Energy => match self.energy_curve {
+                Some(curve) => Cow::Borrowed(curve),
+                None => Cow::Owned(energy_time_curve(self.samples)),
+            },
+            _ => Cow::Borrowed(self.samples),
+        }
+    }
+
+    /// Converts a span of plotted values to the (low, high) y values of its column in the plot.
+    fn column_span(&self, span: &[f32], peak: f32) -> (f64, f64) {
+        match self.scale {
+            AmplitudeScale::Energy => {
+                let max = span.iter().fold(self.min_db, |max, &db| max.max(db));
+                (self.min_db as f64, max as f64)
+            }
+            AmplitudeScale::Linear => {
+                let (min, max) = span.iter().fold((0.0_f32, 0.0_f32), |(min, max), &s| {
+                    (min.min(s), max.max(s))
+                });
+                ((min / peak) as f64, (max / peak) as f64)
+            }
+            AmplitudeScale::Log => {
+                let max = span.iter().fold(0.0_f32, |max, s| max.max(s.abs()));
+                let db = (20.0 * (max / peak).log10()).max(self.min_db);
+                (self.min_db as f64, db as f64)
+            }
+        }
+    }
+
+    fn render_plot(&self, area: Rect, buf: &mut Buffer) {
+        let peak = self.samples.iter().fold(0.0_f32, |max, s| max.max(s.abs()));
+        if peak <= 0.0 || !peak.is_finite() {
+            return;
+        }
+
+        let duration_ms = self.duration_ms() as f64;
+        let values = self.plotted_values();
+        // Braille markers give two dots per cell horizontally.
+        let columns = (area.width as usize * 2).max(1);
+        let samples_per_column = values.len().div_ceil(columns).max(1);
+
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([0.0, duration_ms])
+            .y_bounds(self.y_bounds())
+            .paint(|ctx| {
+                for (i, span) in values.chunks(samples_per_column).enumerate() {
+                    let x = (i * samples_per_column) as f64 / self.sample_rate as f64 * 1000.0;
+                    let (low, high) = self.column_span(span, peak);
+                    ctx.draw(&Line::new(x, low, x, high, self.color));
+                }
+                if let Some(window) = self.window {
+                    let [y_min, y_max] = self.y_bounds();
+                    for x in [window.start_ms as f64, window.end_ms as f64] {
+                        ctx.draw(&Line::new(x, y_min, x, y_max, self.window_color));
+                    }
+                }
+                if let Some(cursor_ms) = self.cursor_ms {
+                    let [y_min, y_max] = self.y_bounds();
+                    let x = cursor_ms as f64;
+                    ctx.draw(&Line::new(x, y_min, x, y_max, Color::White));
+                }
+            })
+            .render(area, buf);
+    }
+
+    fn render_time_labels(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new("0 ms")
+            .alignment(Alignment::Left)
+            .render(area, buf);
+        Paragraph::new(format!("{:.1} ms", self.duration_ms()))
+            .alignment(Alignment::Right)
+            .render(area, buf);
+        if let Some(window) = self.window {
+            Paragraph::new(format!(
+                "Window: {:.1}-{:.1} ms",
+                window.start_ms, window.end_ms
+            ))
+            .alignment(Alignment::Center)
+            .render(area, buf);
+        }
+    }
+
+    fn render_cursor_label(&self, area: Rect, buf: &mut Buffer) {
+        let Some(cursor_ms) = self.cursor_ms else {
+            return;
+        };
+        let delay_ms = cursor_ms - self.peak_ms();
+        let distance_m = delay_ms / 1000.0 * SPEED_OF_SOUND;
+        Paragraph::new(format!(
+            "{:.2} ms  Δ{:.2} ms  {:.2} m",
+            cursor_ms, delay_ms, distance_m
+        ))
+        .alignment(Alignment::Right)
+        .render(area, buf);
+    }
+}
+
+impl Widget for ImpulseResponse<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.height < 2 || self.samples.is_empty() || self.sample_rate <= 0.0 {
+            return;
+        }
+
+        let [mut plot_area, label_area] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(inner);
+        if self.cursor_ms.is_some() && plot_area.height > 1 {
+            let [cursor_label_area, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(0)]).areas(plot_area);
+            self.render_cursor_label(cursor_label_area, buf);
+            plot_area = rest;
+        }
+        self.render_plot(plot_area, buf);
+        self.render_time_labels(label_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn impulse_peaks_at_its_arrival() {
+        let mut samples = vec![0.0; 256];
+        samples[100] = 0.5;
+        let etc = energy_time_curve(&samples);
+        assert_eq!(etc.len(), samples.len());
+        assert_eq!(etc[100], 0.0);
+        assert!(etc.iter().all(|&db| db <= 0.0));
+        // Far from the impulse, outside the reach of the Hilbert taps, there is no energy.
+        assert_eq!(etc[0], -200.0);
+        assert_eq!(etc[255], -200.0);
+    }
+
+    #[test]
+    fn envelope_of_sine_is_flat() {
+        // The analytic signal of a sine has a constant magnitude, so away from the edges, where
+        // the taps run past the samples, the energy-time curve is flat.
+        let samples: Vec<f32> = (0..2048)
+            .map(|n| (2.0 * PI * 6000.0 * n as f32 / 48000.0).sin())
+            .collect();
+        let etc = energy_time_curve(&samples);
+        let middle = &etc[HILBERT_TAPS..samples.len() - HILBERT_TAPS];
+        let (min, max) = middle
+            .iter()
+            .fold((0.0_f32, -200.0_f32), |(min, max), &db| {
+                (min.min(db), max.max(db))
+            });
+        assert!(max - min < 0.1, "{min} to {max}");
+    }
+
+    #[test]
+    fn window_covers_samples_in_range() {
+        let window = TimeWindow::new(1.0, 2.5);
+        assert_eq!(window.sample_range(1000.0, 10), 1..3);
+        assert_eq!(window.sample_range(1000.0, 2), 1..2);
+        assert_eq!(window.sample_range(1000.0, 0), 0..0);
+        let samples = [0.0, 1.0, 2.0, 3.0];
+        assert_eq!(window.apply(&samples, 1000.0), &[1.0, 2.0]);
+    }
+
+    #[test]
+    fn reversed_window_is_swapped() {
+        let mut window = TimeWindow::new(1.0, 3.0);
+        window.end_ms = 0.0;
+        assert_eq!(window.sample_range(1000.0, 10), 0..1);
+        assert_eq!(window.apply(&[5.0, 6.0, 7.0], 1000.0), &[5.0]);
+        assert_eq!(TimeWindow::new(3.0, -1.0), TimeWindow::new(0.0, 3.0));
+    }
+
+    #[test]
+    fn shifted_window_keeps_its_length() {
+        let mut window = TimeWindow::new(2.0, 5.0);
+        window.shift(-10.0);
+        assert_eq!(window, TimeWindow::new(0.0, 3.0));
+        window.shift(1.5);
+        assert_eq!(window, TimeWindow::new(1.5, 4.5));
+    }
+
+    #[test]
+    fn renders_reversed_window_and_cursor() {
+        use ratatui::{buffer::Buffer, layout::Rect};
+
+        let mut samples = vec![0.0; 480];
+        samples[48] = 1.0;
+        let window = TimeWindow {
+            start_ms: 8.0,
+            end_ms: 2.0,
+        };
+        for scale in [
+            AmplitudeScale::Linear,
+            AmplitudeScale::Log,
+            AmplitudeScale::Energy,
+        ] {
+            let area = Rect::new(0, 0, 40, 10);
+            let mut buf = Buffer::empty(area);
+            ImpulseResponse::new(&samples, 48000.0)
+                .scale(scale)
+                .window(window)
+                .cursor(5.0)
+                .render(area, &mut buf);
+            let text: String = buf.content.iter().map(|cell| cell.symbol()).collect();
+            // The cursor lies 4 ms after the peak at 1 ms.
+            assert!(text.contains("Δ4.00 ms  1.37 m"), "{scale:?}: {text}");
+        }
+    }
+
+    #[test]
+    fn plots_precomputed_energy_curve() {
+        let samples = [1.0, 0.0, 0.0];
+        let curve = [0.0, -10.0, -20.0];
+        let widget = ImpulseResponse::new(&samples, 48000.0)
+            .scale(AmplitudeScale::Energy)
+            .energy_curve(&curve);
+        assert_eq!(&*widget.plotted_values(), &curve);
+    }
+}
@@ -0,0 +1,31 @@
+//! Standard band layouts, as tables of center frequencies in Hz.
+//!
+//! The tables hold the nominal ISO 266 center frequencies, so they match the labels of other
+//! analyzers exactly instead of drifting with the float math of computing them at runtime. Being
+//! `const`, they can be used in const contexts, e.g. to size arrays.
+//!
+//! ```
+//! use rataudio_rta::{Band, RTA, layout::ISO_THIRD_OCTAVE};
+//!
+//! const BAND_COUNT: usize = ISO_THIRD_OCTAVE.len();
+//! let levels = [0.5; BAND_COUNT];
+//! let bands = ISO_THIRD_OCTAVE
+//!     .iter()
+//!     .zip(levels)
+//!     .map(|(&frequency, level)| Band::new(level, frequency))
+//!     .collect();
+//! let rta = RTA::new(bands, -60.0);
+//! ```
+
+/// The nominal 1/1-octave band center frequencies from 31.5 Hz to 16 kHz.
+///
+/// 31.5 Hz is stored as 31, as on graphic equalizers, since band frequencies are whole numbers.
+pub const ISO_OCTAVE: [u16; 10] = [31, 63, 125, 250, 500, 1000, 2000, 4000, 8000, 16000];
+
+/// The nominal 1/3-octave band center frequencies from 20 Hz to 20 kHz.
+///
+/// 31.5 Hz is stored as 31, as on graphic equalizers, since band frequencies are whole numbers.
+pub const ISO_THIRD_OCTAVE: [u16; 31] = [
+    20, 25, 31, 40, 50, 63, 80, 100, 125, 160, 200, 250, 315, 400, 500, 630, 800, 1000, 1250, 1600,
+    2000, 2500, 3150, 4000, 5000, 6300, 8000, 10000, 12500, 16000, 20000,
+];
@@ -1,4 +1,82 @@
+mod averaging;
+mod band_editor;
+mod big_number;
+mod bin_table;
+mod block_assembler;
+mod calibration;
+mod chromagram;
+#[cfg(feature = "config")]
+mod config;
+mod decay;
+mod diagnostics;
+mod export;
+mod filter;
+mod flux;
+mod geometry;
+mod import;
+mod impulse_response;
+pub mod layout;
+mod load;
+mod loudness_history;
+mod masking;
+mod music;
+mod number_format;
+mod overlay;
+mod pitch;
+mod polarity;
 mod rendering;
 mod rta;
+#[cfg(feature = "persist")]
+mod settings;
+mod spl;
+mod state;
+mod theme;
+mod trace;
+mod trace_list;
+mod tuner;
+mod waterfall;
 
-pub use rta::{Band, RTA};
+pub use averaging::BandAverager;
+pub use band_editor::{BandEditor, BandEditorState};
+pub use big_number::BigNumber;
+pub use bin_table::BinTable;
+pub use block_assembler::BlockAssembler;
+pub use calibration::MicCalibration;
+pub use chromagram::Chromagram;
+#[cfg(feature = "config")]
+pub use config::{BandConfig, ConfigError, RtaConfig};
+pub use decay::{DecayTable, DecayTimes, schroeder_curve};
+pub use diagnostics::{ChannelDiagnostics, DiagnosticThresholds, DiagnosticsStrip};
+#[cfg(feature = "clipboard")]
+pub use export::copy_to_clipboard;
+pub use export::{
+    Delimiter, format_spectrum, write_impulse_response_wav, write_rew, write_rew_text, write_wav,
+};
+pub use filter::HighPassFilter;
+pub use flux::SpectralFlux;
+pub use geometry::RtaGeometry;
+pub use import::{parse_frequency_response, read_frequency_response};
+pub use impulse_response::{
+    AmplitudeScale, ImpulseResponse, SPEED_OF_SOUND, TimeWindow, energy_time_curve,
+};
+pub use load::{LoadMonitor, QualityIndicator};
+pub use loudness_history::LoudnessHistory;
+pub use masking::masking_threshold;
+pub use music::{Chroma, NOTE_NAMES, frequency_to_midi, midi_to_frequency, note_name};
+pub use number_format::NumberFormat;
+pub use overlay::{Overlay, RtaRenderContext};
+pub use pitch::{Pitch, PitchDetector};
+pub use polarity::{Polarity, PolarityIndicator};
+pub use rta::{
+    AlertBlink, AxisStyle, Band, BarStyle, CODEC_ARTIFACT_FREQUENCIES, ColorMode, Comparison,
+    DisplayScale, ErrorBarStyle, FrequencyAxis, PartialRounding, RTA, TopRuler,
+};
+#[cfg(feature = "persist")]
+pub use settings::{PersistentSettings, Settings};
+pub use spl::{FrequencyWeighting, SplMeter, SplMeterState, TimeWeighting};
+pub use state::{RTAState, RenderStats};
+pub use theme::Theme;
+pub use trace::{FrequencyPoint, Trace, TraceKind, TraceStore, resample_curve};
+pub use trace_list::TraceList;
+pub use tuner::Tuner;
+pub use waterfall::{DecaySettings, SpectralDecay, Waterfall};
@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use ratatui::{
+    prelude::{Buffer, Rect, Widget},
+    style::{Color, Stylize},
+    text::Line,
+};
+
+/// The number of consecutive missed deadlines after which quality is reduced.
+const MISSES_BEFORE_REDUCING: u32 = 5;
+
+/// The number of consecutive frames with plenty of headroom after which quality is restored.
+const HEADROOM_FRAMES_BEFORE_RESTORING: u32 = 100;
+
+/// The smoothed load below which there is plenty of headroom.
+const HEADROOM_LOAD: f32 = 0.4;
+
+/// The most reduction steps allowed, a frame 65536 times smaller than at full quality.
+const MAX_REDUCTION: u32 = 16;
+
+/// Monitors how long analysis takes compared to its deadline and reduces quality when it
+/// consistently falls behind, so the UI stays responsive on weak hardware.
+///
+/// Record the time each analysis took, then size frames with [`LoadMonitor::frame_size`] or
+/// hops with [`LoadMonitor::hop_size`]. Quality is reduced one step at a time after repeated
+/// missed deadlines, and restored once analysis has had plenty of headroom for a while.
+///
+/// ```
+/// use std::time::Duration;
+/// use rataudio_rta::LoadMonitor;
+///
+/// let mut monitor = LoadMonitor::new(Duration::from_millis(16));
+/// for _ in 0..5 {
+///     monitor.record(Duration::from_millis(30));
+/// }
+/// assert!(monitor.is_reduced());
+/// assert_eq!(monitor.frame_size(16384), 8192);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoadMonitor {
+    /// The time available for each analysis.
+    deadline: Duration,
+    /// The current number of reduction steps, where 0 is full quality.
+    reduction: u32,
+    /// The largest number of reduction steps.
+    max_reduction: u32,
+    /// Exponentially smoothed analysis time relative to the deadline.
+    load: f32,
+    /// The number of consecutive missed deadlines.
+    misses: u32,
+    /// The number of consecutive frames with plenty of headroom.
+    headroom_frames: u32,
+}
+
+impl LoadMonitor {
+    /// Creates a new `LoadMonitor` for analyses that must finish within `deadline`, typically
+    /// the hop interval. Quality can be reduced by up to 3 steps.
+    pub fn new(deadline: Duration) -> Self {
+        LoadMonitor {
+            deadline,
+            reduction: 0,
+            max_reduction: 3,
+            load: 0.0,
+            misses: 0,
+            headroom_frames: 0,
+        }
+    }
+
+    /// Sets how many steps quality can be reduced by, at most 16.
+    pub fn max_reduction(mut self, steps: u32) -> Self {
+        self.max_reduction = steps.min(MAX_REDUCTION);
+        self
+    }
+
+    /// Records how long an analysis took. Returns whether the quality changed.
+    pub fn record(&mut self, elapsed: Duration) -> bool {
+        let load = elapsed.as_secs_f32() / self.deadline.as_secs_f32().max(f32::EPSILON);
+        self.load += 0.2 * (load - self.load);
+
+        if elapsed > self.deadline {
+            self.misses += 1;
+            self.headroom_frames = 0;
+        } else {
+            self.misses = 0;
+            if self.load < HEADROOM_LOAD {
+                self.headroom_frames += 1;
+            } else {
+                self.headroom_frames = 0;
+            }
+        }
+
+        if self.misses >= MISSES_BEFORE_REDUCING && self.reduction < self.max_reduction {
+            self.reduction += 1;
+        } else if self.headroom_frames >= HEADROOM_FRAMES_BEFORE_RESTORING && self.reduction > 0 {
+            self.reduction -= 1;
+        } else {
+            return false;
+        }
+        self.misses = 0;
+        self.headroom_frames = 0;
+        true
+    }
+
+    /// The number of steps quality is currently reduced by, where 0 is full quality.
+    pub fn reduction(&self) -> u32 {
+        self.reduction
+    }
+
+    /// Whether quality is currently reduced.
+    pub fn is_reduced(&self) -> bool {
+        self.reduction > 0
+    }
+
+    /// The smoothed analysis time relative to the deadline, where 1.0 is exactly on time.
+    pub fn load(&self) -> f32 {
+        self.load
+    }
+
+    /// The frame size to use instead of `full_size`, halved for each reduction step.
+    pub fn frame_size(&self, full_size: usize) -> usize {
+        full_size.checked_shr(self.reduction).unwrap_or(0).max(1)
+    }
+
+    /// The hop size to use instead of `full_size`, doubled for each reduction step and saturating
+    /// at `usize::MAX`.
+    pub fn hop_size(&self, full_size: usize) -> usize {
+        1_usize
+            .checked_shl(self.reduction)
+            .map_or(usize::MAX, |factor| full_size.saturating_mul(factor))
+    }
+}
+
+/// A single-line indicator that shows when a [`LoadMonitor`] has reduced quality.
+///
+/// Nothing is drawn while running at full quality.
+#[derive(Debug, Clone)]
+pub struct QualityIndicator<'a> {
+    monitor: &'a LoadMonitor,
+}
+
+impl<'a> QualityIndicator<'a> {
+    /// Creates a new `QualityIndicator` for `monitor`.
+    pub fn new(monitor: &'a LoadMonitor) -> Self {
+        QualityIndicator { monitor }
+    }
+}
+
+impl Widget for QualityIndicator<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if !self.monitor.is_reduced() {
+            return;
+        }
+        Line::from(format!(
+            "⚠ Reduced quality ({}/{}, load {:.0}%)",
+            self.monitor.reduction(),
+            self.monitor.max_reduction,
+            self.monitor.load() * 100.0
+        ))
+        .fg(Color::Yellow)
+        .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overloaded(max_reduction: u32) -> LoadMonitor {
+        let mut monitor = LoadMonitor::new(Duration::from_millis(10)).max_reduction(max_reduction);
+        for _ in 0..1000 {
+            monitor.record(Duration::from_millis(20));
+        }
+        monitor
+    }
+
+    #[test]
+    fn reduces_and_restores_quality() {
+        let mut monitor = overloaded(3);
+        assert_eq!(monitor.reduction(), 3);
+        assert_eq!(monitor.frame_size(4096), 512);
+        assert_eq!(monitor.hop_size(1024), 8192);
+        for _ in 0..HEADROOM_FRAMES_BEFORE_RESTORING * 10 {
+            monitor.record(Duration::ZERO);
+        }
+        assert!(!monitor.is_reduced());
+        assert_eq!(monitor.frame_size(4096), 4096);
+    }
+
+    #[test]
+    fn large_reduction_saturates() {
+        let monitor = overloaded(u32::MAX);
+        assert_eq!(monitor.reduction(), MAX_REDUCTION);
+        assert_eq!(monitor.frame_size(4096), 1);
+        assert_eq!(monitor.hop_size(usize::MAX / 2), usize::MAX);
+    }
+}
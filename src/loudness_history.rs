@@ -0,0 +1,201 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::{Color, Stylize},
+    symbols::Marker,
+    text::{Line as TextLine, Span},
+    widgets::{
+        Block, Paragraph,
+        canvas::{Canvas, Context, Line},
+    },
+};
+
+/// A scrolling graph of loudness over time, with the most recent value at the right edge.
+///
+/// The loudness values are measured by the application and passed in as evenly spaced histories,
+/// oldest first. Momentary and short-term loudness are plotted on top of a line at the target
+/// level.
+#[derive(Debug, Clone)]
+pub struct LoudnessHistory<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// Momentary loudness values in LUFS, oldest first.
+    momentary: &'a [f32],
+    /// Short-term loudness values in LUFS, oldest first, if any.
+    short_term: Option<&'a [f32]>,
+    /// The time between two consecutive values in seconds.
+    interval_s: f32,
+    /// The length of the visible history in seconds.
+    duration_s: f32,
+    /// The target loudness in LUFS, if any.
+    target: Option<f32>,
+    /// The loudness at the bottom of the graph in LUFS.
+    min_lufs: f32,
+    /// The loudness at the top of the graph in LUFS.
+    max_lufs: f32,
+    /// The color of the momentary loudness.
+    momentary_color: Color,
+    /// The color of the short-term loudness.
+    short_term_color: Color,
+    /// The color of the target line.
+    target_color: Color,
+}
+
+impl<'a> LoudnessHistory<'a> {
+    /// Creates a new `LoudnessHistory` plotting `momentary` loudness values in LUFS, measured
+    /// `interval_s` seconds apart.
+    ///
+    /// Shows the last 60 seconds between -50 and -10 LUFS with a target of -23 LUFS (EBU R 128).
+    pub fn new(momentary: &'a [f32], interval_s: f32) -> Self {
+        LoudnessHistory {
+            block: None,
+            momentary,
+            short_term: None,
+            interval_s,
+            duration_s: 60.0,
+            target: Some(-23.0),
+            min_lufs: -50.0,
+            max_lufs: -10.0,
+            momentary_color: Color::Cyan,
+            short_term_color: Color::Yellow,
+            target_color: Color::Green,
+        }
+    }
+
+    /// Also plots `short_term` loudness values in LUFS, measured at the same interval.
+    pub fn short_term(mut self, short_term: &'a [f32]) -> Self {
+        self.short_term = Some(short_term);
+        self
+    }
+
+    /// Sets the length of the visible history in seconds.
+    pub fn duration(mut self, duration_s: f32) -> Self {
+        self.duration_s = duration_s;
+        self
+    }
+
+    /// Sets the target loudness in LUFS, or hides the target line with `None`.
+    pub fn target(mut self, target: Option<f32>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets the loudness range of the graph in LUFS.
+    pub fn range(mut self, min_lufs: f32, max_lufs: f32) -> Self {
+        self.min_lufs = min_lufs;
+        self.max_lufs = max_lufs;
+        self
+    }
+
+    /// Sets the colors of the momentary and short-term loudness.
+    pub fn colors(mut self, momentary: Color, short_term: Color) -> Self {
+        self.momentary_color = momentary;
+        self.short_term_color = short_term;
+        self
+    }
+
+    /// Sets the color of the target line.
+    pub fn target_color(mut self, color: Color) -> Self {
+        self.target_color = color;
+        self
+    }
+
+    /// Surrounds the `LoudnessHistory` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Draws `values` as connected line segments, ending at time 0.
+    fn draw_history(&self, ctx: &mut Context, values: &[f32], color: Color) {
+        let clamp = |lufs: f32| {
+            if lufs.is_nan() {
+                self.min_lufs as f64
+            } else {
+                lufs.clamp(self.min_lufs, self.max_lufs) as f64
+            }
+        };
+        let last = values.len().saturating_sub(1);
+        let visible = (self.duration_s / self.interval_s).ceil() as usize + 1;
+        let start = values.len().saturating_sub(visible);
+        let time = |i: usize| -((last - i) as f64 * self.interval_s as f64);
+
+        for i in start.max(1)..values.len() {
+            ctx.draw(&Line::new(
+                time(i - 1),
+                clamp(values[i - 1]),
+                time(i),
+                clamp(values[i]),
+                color,
+            ));
+        }
+    }
+
+    fn render_plot(&self, area: Rect, buf: &mut Buffer) {
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([-self.duration_s as f64, 0.0])
+            .y_bounds([self.min_lufs as f64, self.max_lufs as f64])
+            .paint(|ctx| {
+                if let Some(target) = self.target {
+                    let y = target as f64;
+                    ctx.draw(&Line::new(
+                        -self.duration_s as f64,
+                        y,
+                        0.0,
+                        y,
+                        self.target_color,
+                    ));
+                }
+                ctx.layer();
+                if let Some(short_term) = self.short_term {
+                    self.draw_history(ctx, short_term, self.short_term_color);
+                }
+                self.draw_history(ctx, self.momentary, self.momentary_color);
+            })
+            .render(area, buf);
+    }
+
+    fn render_labels(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(format!("-{:.0} s", self.duration_s))
+            .alignment(Alignment::Left)
+            .render(area, buf);
+        Paragraph::new("now")
+            .alignment(Alignment::Right)
+            .render(area, buf);
+
+        let mut legend = vec![Span::raw("M").fg(self.momentary_color)];
+        if self.short_term.is_some() {
+            legend.push(Span::raw(" S").fg(self.short_term_color));
+        }
+        if let Some(target) = self.target {
+            legend.push(Span::raw(format!(" Target {:.0} LUFS", target)).fg(self.target_color));
+        }
+        Paragraph::new(TextLine::from(legend))
+            .alignment(Alignment::Center)
+            .render(area, buf);
+    }
+}
+
+impl Widget for LoudnessHistory<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.height < 2
+            || self.interval_s <= 0.0
+            || self.duration_s <= 0.0
+            || self.min_lufs >= self.max_lufs
+        {
+            return;
+        }
+
+        let [plot_area, label_area] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(inner);
+        self.render_plot(plot_area, buf);
+        self.render_labels(label_area, buf);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::rta::Band;
+
+/// How far the masking threshold lies below the level of a masker at its own frequency, in dB.
+const MASKING_OFFSET_DB: f32 = 10.0;
+
+/// Estimates the psychoacoustic masking threshold at each band: the level below which content in
+/// the band is likely inaudible next to the louder content in the other bands.
+///
+/// Every band masks its neighbors with the Schroeder spreading function over the Bark scale,
+/// which falls off faster towards lower than towards higher frequencies. The masking of all bands
+/// is summed in power and lowered by a fixed offset. This is a simple estimate that ignores the
+/// absolute threshold of hearing and the difference between tonal and noise maskers.
+///
+/// Returns the threshold in dB for each band, on the same scale as the bands, or NaN for bands
+/// without a frequency. Show it on the meter with [`crate::RTA::masking_curve`].
+///
+/// ```
+/// use rataudio_rta::{Band, masking_threshold};
+///
+/// let bands = [Band::new(1.0, 1000), Band::new(0.0, 1100), Band::new(0.0, 8000)];
+/// let threshold = masking_threshold(&bands, -60.0);
+/// assert!(threshold[1] > -30.0);
+/// assert!(threshold[2] < -60.0);
+/// ```
+pub fn masking_threshold(bands: &[Band], min_db: f32) -> Vec<f32> {
+    let maskers: Vec<(f32, f32)> = bands
+        .iter()
+        .filter(|band| !band.value.is_nan() && band.display_value() > 0.0)
+        .filter_map(|band| Some((bark(band.frequency? as f32), band.get_db(min_db))))
+        .collect();
+    bands
+        .iter()
+        .map(|band| {
+            let Some(frequency) = band.frequency else {
+                return f32::NAN;
+            };
+            let z = bark(frequency as f32);
+            let power: f32 = maskers
+                .iter()
+                .map(|&(masker_z, db)| {
+                    10_f32.powf((db + spreading_db(z - masker_z) - MASKING_OFFSET_DB) / 10.0)
+                })
+                .sum();
+            if power > 0.0 {
+                10.0 * power.log10()
+            } else {
+                f32::NEG_INFINITY
+            }
+        })
+        .collect()
+}
+
+/// The critical band rate in Bark of `frequency` in Hz, after Zwicker and Terhardt.
+fn bark(frequency: f32) -> f32 {
+    13.0 * (0.00076 * frequency).atan() + 3.5 * (frequency / 7500.0).powi(2).atan()
+}
+
+/// The Schroeder spreading function: how much a masker `dz` Bark below the masked frequency
+/// contributes to the masking there, in dB.
+fn spreading_db(dz: f32) -> f32 {
+    15.81 + 7.5 * (dz + 0.474) - 17.5 * (1.0 + (dz + 0.474).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bark_scale_matches_zwicker() {
+        assert!((bark(1000.0) - 8.5).abs() < 0.1, "{}", bark(1000.0));
+        assert!((bark(4000.0) - 17.3).abs() < 0.2, "{}", bark(4000.0));
+    }
+
+    #[test]
+    fn spreading_falls_off_faster_downwards() {
+        assert!(spreading_db(0.0).abs() < 0.01);
+        // Far from the masker, the slopes approach -10 dB per Bark upwards and -25 dB per Bark
+        // downwards.
+        assert!((spreading_db(8.0) - spreading_db(7.0) - -10.0).abs() < 0.5);
+        assert!((spreading_db(-7.0) - spreading_db(-8.0) - 25.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn threshold_lies_below_masker() {
+        let bands = [
+            Band::new(0.0, 500),
+            Band::new(0.5, 1000),
+            Band::new(0.0, 2000),
+            Band {
+                frequency: None,
+                ..Band::new(0.0, 0)
+            },
+        ];
+        let threshold = masking_threshold(&bands, -60.0);
+        let masker_db = bands[1].get_db(-60.0);
+        assert!((threshold[1] - (masker_db - MASKING_OFFSET_DB)).abs() < 0.01);
+        // 2 kHz lies about as far above the masker on the Bark scale as 500 Hz lies below it, but
+        // is masked more.
+        assert!(threshold[2] > threshold[0]);
+        assert!(threshold[3].is_nan());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::trace::FrequencyPoint;
+
+/// The names of the 12 pitch classes, starting at C.
+pub const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// The MIDI note number of A4.
+const A4_MIDI: f32 = 69.0;
+
+/// The frequency of A4 in Hz.
+const A4_HZ: f32 = 440.0;
+
+/// Converts a frequency in Hz to a fractional MIDI note number, where 69.0 is A4 at 440 Hz.
+pub fn frequency_to_midi(frequency: f32) -> f32 {
+    A4_MIDI + 12.0 * (frequency / A4_HZ).log2()
+}
+
+/// Converts a fractional MIDI note number to a frequency in Hz.
+pub fn midi_to_frequency(note: f32) -> f32 {
+    A4_HZ * 2_f32.powf((note - A4_MIDI) / 12.0)
+}
+
+/// The name of a MIDI note in scientific pitch notation, e.g. `"C4"` for middle C (60).
+pub fn note_name(note: i32) -> String {
+    let octave = note.div_euclid(12) - 1;
+    format!("{}{}", NOTE_NAMES[note.rem_euclid(12) as usize], octave)
+}
+
+/// The energy of a spectrum folded into the 12 pitch classes, starting at C.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Chroma {
+    /// The linear energy of each pitch class.
+    pub energies: [f32; 12],
+}
+
+impl Chroma {
+    /// The lowest frequency folded into the chroma, A0.
+    pub const MIN_FREQUENCY: f32 = 27.5;
+    /// The highest frequency folded into the chroma, C8.
+    pub const MAX_FREQUENCY: f32 = 4186.0;
+
+    /// Folds a frequency response into pitch classes, weighting each point by its energy.
+    pub fn from_points(points: &[FrequencyPoint]) -> Self {
+        let mut chroma = Chroma::default();
+        for point in points {
+            chroma.add(point.frequency, 10_f32.powf(point.magnitude_db / 10.0));
+        }
+        chroma
+    }
+
+    /// Folds a linear magnitude spectrum into pitch classes, e.g. the output of an FFT where bin
+    /// `k` is centered at `k * bin_width_hz`.
+    pub fn from_magnitudes(magnitudes: &[f32], bin_width_hz: f32) -> Self {
+        let mut chroma = Chroma::default();
+        for (k, magnitude) in magnitudes.iter().enumerate() {
+            chroma.add(k as f32 * bin_width_hz, magnitude * magnitude);
+        }
+        chroma
+    }
+
+    fn add(&mut self, frequency: f32, energy: f32) {
+        if !(Self::MIN_FREQUENCY..=Self::MAX_FREQUENCY).contains(&frequency) || !energy.is_finite()
+        {
+            return;
+        }
+        let pitch_class = (frequency_to_midi(frequency).round() as i32).rem_euclid(12);
+        self.energies[pitch_class as usize] += energy;
+    }
+
+    /// The energies relative to the strongest pitch class, from 0.0 to 1.0.
+    pub fn normalized(&self) -> [f32; 12] {
+        let max = self.energies.iter().fold(0.0_f32, |max, &e| max.max(e));
+        if max <= 0.0 {
+            return [0.0; 12];
+        }
+        self.energies.map(|e| e / max)
+    }
+
+    /// The index of the strongest pitch class, or `None` if there is no energy.
+    pub fn strongest(&self) -> Option<usize> {
+        self.energies
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| **e > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_frequency_and_midi() {
+        assert_eq!(frequency_to_midi(440.0), 69.0);
+        assert!((frequency_to_midi(261.63) - 60.0).abs() < 1e-3);
+        assert!((frequency_to_midi(880.0) - 81.0).abs() < 1e-4);
+        assert!((midi_to_frequency(60.0) - 261.63).abs() < 0.01);
+        assert!((midi_to_frequency(frequency_to_midi(1234.5)) - 1234.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn names_notes() {
+        assert_eq!(note_name(60), "C4");
+        assert_eq!(note_name(69), "A4");
+        assert_eq!(note_name(21), "A0");
+        assert_eq!(note_name(0), "C-1");
+    }
+
+    #[test]
+    fn folds_octaves_into_pitch_classes() {
+        // A2, A3 and A4 all land on A, E5 on E.
+        let points = [
+            FrequencyPoint::new(110.0, 0.0),
+            FrequencyPoint::new(220.0, 0.0),
+            FrequencyPoint::new(440.0, 0.0),
+            FrequencyPoint::new(659.26, -10.0),
+            FrequencyPoint::new(10.0, 20.0),
+        ];
+        let chroma = Chroma::from_points(&points);
+        assert!((chroma.energies[9] - 3.0).abs() < 1e-6);
+        assert!((chroma.energies[4] - 0.1).abs() < 1e-6);
+        assert_eq!(chroma.strongest(), Some(9));
+        assert!((chroma.normalized()[4] - 0.1 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn folds_fft_bins() {
+        let mut magnitudes = vec![0.0; 64];
+        // Bin 22 of 20 Hz bins is 440 Hz.
+        magnitudes[22] = 2.0;
+        let chroma = Chroma::from_magnitudes(&magnitudes, 20.0);
+        assert_eq!(chroma.energies[9], 4.0);
+        assert_eq!(chroma.energies.iter().sum::<f32>(), 4.0);
+    }
+
+    #[test]
+    fn silence_has_no_strongest_pitch_class() {
+        let chroma = Chroma::from_magnitudes(&[0.0; 16], 20.0);
+        assert_eq!(chroma.strongest(), None);
+        assert_eq!(chroma.normalized(), [0.0; 12]);
+    }
+}
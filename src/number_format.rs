@@ -0,0 +1,76 @@
+/// How the numeric labels of an `RTA` are formatted, so the display can be localized.
+///
+/// ```
+/// use rataudio_rta::NumberFormat;
+///
+/// let format = NumberFormat {
+///     decimal_separator: ',',
+///     peak_label: "Spitze".to_string(),
+///     ..Default::default()
+/// };
+/// assert_eq!(format.decimal(-3.5, 1), "-3,5");
+/// assert_eq!(format.frequency(1500), "1,5k");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+    /// The character between the integer and fractional part of a number.
+    pub decimal_separator: char,
+    /// The unit of levels, e.g. `"dB"`.
+    pub db_unit: String,
+    /// The unit of frequencies, e.g. `"Hz"`.
+    pub hz_unit: String,
+    /// The prefix for thousands on the frequency axis, e.g. `"k"` in `1.5k`.
+    pub kilo_prefix: String,
+    /// The name of the peak level readout.
+    pub peak_label: String,
+    /// The name of the peak band readout.
+    pub band_label: String,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            decimal_separator: '.',
+            db_unit: "dB".to_string(),
+            hz_unit: "Hz".to_string(),
+            kilo_prefix: "k".to_string(),
+            peak_label: "Peak".to_string(),
+            band_label: "Band".to_string(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Formats `value` with `precision` decimals and the decimal separator.
+    pub fn decimal(&self, value: f64, precision: usize) -> String {
+        let text = format!("{:.*}", precision, value);
+        if self.decimal_separator == '.' {
+            text
+        } else {
+            text.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+
+    /// Formats a level in dB with `precision` decimals and the unit.
+    pub fn db(&self, db: f32, precision: usize) -> String {
+        format!("{}{}", self.decimal(db as f64, precision), self.db_unit)
+    }
+
+    /// Formats a frequency for the frequency axis, using the kilo prefix from 1 kHz.
+    pub fn frequency(&self, frequency: u16) -> String {
+        if frequency >= 10000 {
+            format!("{:.0}{}", frequency as f64 / 1000.0, self.kilo_prefix)
+        } else if frequency >= 1000 {
+            let label = format!("{:.1}", frequency as f64 / 1000.0);
+            // Drop the decimal of whole kHz, e.g. 2k rather than 2.0k.
+            let label = label.strip_suffix(".0").unwrap_or(&label);
+            format!(
+                "{}{}",
+                label.replace('.', &self.decimal_separator.to_string()),
+                self.kilo_prefix
+            )
+        } else {
+            format!("{}", frequency)
+        }
+    }
+}
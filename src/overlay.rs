@@ -0,0 +1,43 @@
+use std::fmt;
+
+use ratatui::buffer::Buffer;
+
+use crate::{geometry::RtaGeometry, rta::Band};
+
+/// Custom drawing on top of an `RTA`, e.g. curves or markers of an app, added with
+/// [`crate::RTA::overlay`].
+///
+/// Overlays are rendered after the bars and built-in curves, in the order they were added.
+pub trait Overlay {
+    /// Draws the overlay into `buf`, using `ctx` to map frequencies and levels to cells.
+    fn render(&self, ctx: &RtaRenderContext, buf: &mut Buffer);
+}
+
+/// A reference to an [`Overlay`] that can be stored in a widget.
+#[derive(Clone, Copy)]
+pub(crate) struct OverlayRef<'a>(pub(crate) &'a dyn Overlay);
+
+impl fmt::Debug for OverlayRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Overlay")
+    }
+}
+
+/// What an [`Overlay`] draws on: the bands of the `RTA` and where they were rendered.
+#[derive(Debug, Clone, Copy)]
+pub struct RtaRenderContext<'a> {
+    pub(crate) bands: &'a [Band],
+    pub(crate) geometry: &'a RtaGeometry,
+}
+
+impl RtaRenderContext<'_> {
+    /// The bands of the meter.
+    pub fn bands(&self) -> &[Band] {
+        self.bands
+    }
+
+    /// Where the meter was rendered, and how frequencies and levels map to cells.
+    pub fn geometry(&self) -> &RtaGeometry {
+        self.geometry
+    }
+}
@@ -0,0 +1,169 @@
+use crate::music::{frequency_to_midi, note_name};
+
+/// A detected pitch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pitch {
+    /// The fundamental frequency in Hz.
+    pub frequency: f32,
+    /// How periodic the signal is, from 0.0 to 1.0.
+    pub confidence: f32,
+}
+
+impl Pitch {
+    /// The MIDI note number nearest to the pitch.
+    pub fn note(&self) -> i32 {
+        frequency_to_midi(self.frequency).round() as i32
+    }
+
+    /// The name of the nearest note, e.g. `"A4"`.
+    pub fn note_name(&self) -> String {
+        note_name(self.note())
+    }
+
+    /// The deviation from the nearest note in cents, from -50 to 50.
+    pub fn cents(&self) -> f32 {
+        let midi = frequency_to_midi(self.frequency);
+        (midi - midi.round()) * 100.0
+    }
+}
+
+/// Detects the pitch of monophonic signals with the YIN algorithm.
+///
+/// Feed it frames from the same [`crate::BlockAssembler`] that feeds the RTA. Frames must be at
+/// least twice as long as the period of the lowest frequency to detect.
+#[derive(Debug, Clone)]
+pub struct PitchDetector {
+    sample_rate: f32,
+    min_frequency: f32,
+    max_frequency: f32,
+    /// The largest normalized difference accepted as a period.
+    threshold: f32,
+}
+
+impl PitchDetector {
+    /// Creates a new `PitchDetector` for signals at `sample_rate`, detecting pitches from 40 Hz
+    /// to 2 kHz.
+    pub fn new(sample_rate: f32) -> Self {
+        PitchDetector {
+            sample_rate,
+            min_frequency: 40.0,
+            max_frequency: 2000.0,
+            threshold: 0.15,
+        }
+    }
+
+    /// Sets the range of pitches to detect in Hz.
+    ///
+    /// Bounds that aren't positive and finite are ignored, keeping the previous bound.
+    pub fn range(mut self, min_frequency: f32, max_frequency: f32) -> Self {
+        let valid = |frequency: f32| frequency.is_finite() && frequency > 0.0;
+        if valid(min_frequency) {
+            self.min_frequency = min_frequency;
+        }
+        if valid(max_frequency) {
+            self.max_frequency = max_frequency;
+        }
+        self
+    }
+
+    /// Sets how aperiodic a signal may be and still count as pitched, from 0.0 to 1.0. Lower
+    /// values reject more noisy signals. Defaults to 0.15.
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Detects the pitch of `frame`, or returns `None` if it has no clear pitch in the range.
+    pub fn detect(&self, frame: &[f32]) -> Option<Pitch> {
+        let min_tau = (self.sample_rate / self.max_frequency).floor().max(2.0) as usize;
+        let max_tau = (self.sample_rate / self.min_frequency).ceil();
+        if !max_tau.is_finite() {
+            return None;
+        }
+        let max_tau = max_tau as usize;
+        if min_tau >= max_tau || max_tau.checked_mul(2).is_none_or(|len| frame.len() < len) {
+            return None;
+        }
+        let window = frame.len() - max_tau;
+
+        // The cumulative mean normalized difference function.
+        let mut cmnd = vec![1.0_f32; max_tau + 1];
+        let mut running_sum = 0.0;
+        for (tau, value) in cmnd.iter_mut().enumerate().skip(1) {
+            let difference: f32 = (0..window)
+                .map(|j| (frame[j] - frame[j + tau]).powi(2))
+                .sum();
+            running_sum += difference;
+            *value = if running_sum > 0.0 {
+                difference * tau as f32 / running_sum
+            } else {
+                1.0
+            };
+        }
+
+        let mut tau = (min_tau..max_tau).find(|&tau| cmnd[tau] < self.threshold)?;
+        while tau + 1 < max_tau && cmnd[tau + 1] < cmnd[tau] {
+            tau += 1;
+        }
+
+        // Refine the period with parabolic interpolation around the minimum.
+        let (a, b, c) = (cmnd[tau - 1], cmnd[tau], cmnd[tau + 1]);
+        let denominator = a - 2.0 * b + c;
+        let offset = if denominator.abs() > f32::EPSILON {
+            (0.5 * (a - c) / denominator).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+
+        Some(Pitch {
+            frequency: self.sample_rate / (tau as f32 + offset),
+            confidence: (1.0 - b).clamp(0.0, 1.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn sine(frequency: f32) -> Vec<f32> {
+        (0..4096)
+            .map(|n| (2.0 * PI * frequency * n as f32 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    #[test]
+    fn detects_sine() {
+        let pitch = PitchDetector::new(SAMPLE_RATE)
+            .detect(&sine(440.0))
+            .unwrap();
+        assert!((pitch.frequency - 440.0).abs() < 0.5, "{pitch:?}");
+        assert!(pitch.confidence > 0.9);
+        assert_eq!(pitch.note_name(), "A4");
+    }
+
+    #[test]
+    fn ignores_pitch_outside_range() {
+        let detector = PitchDetector::new(SAMPLE_RATE).range(500.0, 2000.0);
+        assert_eq!(detector.detect(&sine(220.0)), None);
+    }
+
+    #[test]
+    fn invalid_range_is_ignored() {
+        for min_frequency in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let detector = PitchDetector::new(SAMPLE_RATE).range(min_frequency, 2000.0);
+            let pitch = detector.detect(&sine(440.0)).unwrap();
+            assert!((pitch.frequency - 440.0).abs() < 0.5);
+        }
+    }
+
+    #[test]
+    fn frame_too_short_for_range() {
+        let detector = PitchDetector::new(SAMPLE_RATE).range(1e-30, 2000.0);
+        assert_eq!(detector.detect(&sine(440.0)), None);
+    }
+}
@@ -0,0 +1,158 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Paragraph},
+};
+
+use crate::big_number::BigNumber;
+
+/// How much larger the dominant peak must be than the largest peak of opposite sign for the
+/// polarity to be conclusive.
+const DOMINANCE_RATIO: f32 = 1.2;
+
+/// The polarity of a speaker, as measured with a test pulse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    /// The speaker reproduces the pulse with the same polarity.
+    Positive,
+    /// The speaker reproduces the pulse inverted.
+    Negative,
+    /// The response is too weak or ambiguous to tell.
+    Unknown,
+}
+
+impl Polarity {
+    /// Detects the polarity from the recorded response to a positive test pulse.
+    ///
+    /// The polarity is the sign of the dominant peak of the response. If the pulse that was played
+    /// is negative, invert the result with [`Polarity::inverted`].
+    pub fn detect(response: &[f32]) -> Self {
+        let (max, min) = response.iter().fold((0.0_f32, 0.0_f32), |(max, min), &s| {
+            (max.max(s), min.min(s))
+        });
+        let (positive, negative) = (max, -min);
+        if positive.max(negative) < 1e-6 {
+            Polarity::Unknown
+        } else if positive > negative * DOMINANCE_RATIO {
+            Polarity::Positive
+        } else if negative > positive * DOMINANCE_RATIO {
+            Polarity::Negative
+        } else {
+            Polarity::Unknown
+        }
+    }
+
+    /// The opposite polarity. `Unknown` stays `Unknown`.
+    pub fn inverted(self) -> Self {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Unknown => Polarity::Unknown,
+        }
+    }
+}
+
+/// A widget prominently displaying a measured [`Polarity`] as a large `+` or `-`.
+#[derive(Debug, Clone)]
+pub struct PolarityIndicator<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The polarity to display.
+    polarity: Polarity,
+}
+
+impl<'a> PolarityIndicator<'a> {
+    /// Creates a new `PolarityIndicator` displaying `polarity`.
+    pub fn new(polarity: Polarity) -> Self {
+        PolarityIndicator {
+            block: None,
+            polarity,
+        }
+    }
+
+    /// Surrounds the `PolarityIndicator` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for PolarityIndicator<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.is_empty() {
+            return;
+        }
+
+        let (symbol, label, color) = match self.polarity {
+            Polarity::Positive => ("+", "Polarity positive", Color::Green),
+            Polarity::Negative => ("-", "Polarity inverted", Color::Red),
+            Polarity::Unknown => ("?", "Polarity unknown", Color::Yellow),
+        };
+
+        let [symbol_area, label_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        BigNumber::new(symbol)
+            .style(Style::new().fg(color).bold())
+            .render(symbol_area, buf);
+        Paragraph::new(label)
+            .style(Style::new().fg(color))
+            .alignment(Alignment::Center)
+            .render(label_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A speaker response to a positive pulse: a large positive peak with a smaller undershoot.
+    const RESPONSE: [f32; 8] = [0.0, 0.1, 0.8, 1.0, 0.2, -0.4, -0.1, 0.0];
+
+    #[test]
+    fn detects_in_phase_response() {
+        assert_eq!(Polarity::detect(&RESPONSE), Polarity::Positive);
+    }
+
+    #[test]
+    fn detects_inverted_response() {
+        let inverted = RESPONSE.map(|s| -s);
+        assert_eq!(Polarity::detect(&inverted), Polarity::Negative);
+        assert_eq!(Polarity::detect(&inverted).inverted(), Polarity::Positive);
+    }
+
+    #[test]
+    fn silence_is_unknown() {
+        assert_eq!(Polarity::detect(&[]), Polarity::Unknown);
+        assert_eq!(Polarity::detect(&[0.0; 64]), Polarity::Unknown);
+        assert_eq!(Polarity::detect(&[1e-7, -1e-7]), Polarity::Unknown);
+    }
+
+    #[test]
+    fn noise_is_unknown() {
+        // Uniform noise from a small linear congruential generator peaks about equally far in
+        // both directions.
+        let mut seed = 1_u32;
+        let noise: Vec<f32> = (0..4096)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                seed as f32 / u32::MAX as f32 * 2.0 - 1.0
+            })
+            .collect();
+        assert_eq!(Polarity::detect(&noise), Polarity::Unknown);
+    }
+
+    #[test]
+    fn dominant_peak_must_exceed_ratio() {
+        assert_eq!(Polarity::detect(&[1.2, -1.0]), Polarity::Unknown);
+        assert_eq!(Polarity::detect(&[1.25, -1.0]), Polarity::Positive);
+        assert_eq!(Polarity::detect(&[1.0, -1.2]), Polarity::Unknown);
+        assert_eq!(Polarity::detect(&[1.0, -1.25]), Polarity::Negative);
+    }
+}
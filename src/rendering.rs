@@ -1,168 +1,851 @@
-use std::iter::zip;
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    iter::zip,
+    time::{Instant, SystemTime},
+};
 
 use ratatui::{
-    layout::{Alignment, Constraint, Layout},
+    buffer::Cell,
+    layout::{Alignment, Constraint, Layout, Position},
     prelude::{BlockExt, Buffer, Color, Rect, Widget},
-    widgets::{Block, Borders, Paragraph},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, StatefulWidget},
+};
+
+use crate::{
+    geometry::RtaGeometry,
+    masking::masking_threshold,
+    music::{NOTE_NAMES, frequency_to_midi, note_name},
+    overlay::RtaRenderContext,
+    rta::{
+        AlertBlink, Band, BarStyle, ColorMode, Comparison, DisplayScale, ErrorBarStyle,
+        FrequencyAxis, PartialRounding, RAINBOW, RTA, TopRuler, gradient_color,
+    },
+    state::{RTAState, RenderStats},
+    trace::resample_curve,
 };
 
-use crate::rta::{Band, RTA};
+/// The symbols for a partially filled cell of a vertical bar, indexed by the number of filled
+/// eighths.
+pub(crate) const PARTIAL_BLOCKS: [&str; 8] = [
+    "",
+    ratatui::symbols::bar::ONE_EIGHTH,
+    ratatui::symbols::bar::ONE_QUARTER,
+    ratatui::symbols::bar::THREE_EIGHTHS,
+    ratatui::symbols::bar::HALF,
+    ratatui::symbols::bar::FIVE_EIGHTHS,
+    ratatui::symbols::bar::THREE_QUARTERS,
+    ratatui::symbols::bar::SEVEN_EIGHTHS,
+];
+
+/// The symbols for a partially filled cell of a horizontal bar, indexed by the number of filled
+/// eighths.
+pub(crate) const HORIZONTAL_PARTIAL_BLOCKS: [&str; 8] = [
+    "",
+    ratatui::symbols::block::ONE_EIGHTH,
+    ratatui::symbols::block::ONE_QUARTER,
+    ratatui::symbols::block::THREE_EIGHTHS,
+    ratatui::symbols::block::HALF,
+    ratatui::symbols::block::FIVE_EIGHTHS,
+    ratatui::symbols::block::THREE_QUARTERS,
+    ratatui::symbols::block::SEVEN_EIGHTHS,
+];
+
+/// The fill patterns that tell stacked channels apart in [`ColorMode::Monochrome`].
+const CHANNEL_PATTERNS: [&str; 4] = [
+    ratatui::symbols::block::FULL,
+    ratatui::symbols::shade::DARK,
+    ratatui::symbols::shade::MEDIUM,
+    ratatui::symbols::shade::LIGHT,
+];
+
+/// Ordered dither offsets, in eighths, applied per column of a bar.
+const DITHER_OFFSETS: [f32; 4] = [0.125, 0.625, 0.375, 0.875];
 
 impl Band {
-    fn render(self, area: Rect, width: u16, buf: &mut Buffer) {
-        let value = self.value.clamp(0.0, 1.0);
-
-        let scaled = value * area.height as f32;
-        let full_blocks = scaled.floor() as u16;
-        let fraction = scaled - full_blocks as f32;
-
-        let partial_block = match fraction {
-            f if f >= 7.0 / 8.0 => ratatui::symbols::bar::SEVEN_EIGHTHS,
-            f if f >= 3.0 / 4.0 => ratatui::symbols::bar::THREE_QUARTERS,
-            f if f >= 5.0 / 8.0 => ratatui::symbols::bar::FIVE_EIGHTHS,
-            f if f >= 1.0 / 2.0 => ratatui::symbols::bar::HALF,
-            f if f >= 3.0 / 8.0 => ratatui::symbols::bar::THREE_EIGHTHS,
-            f if f >= 1.0 / 4.0 => ratatui::symbols::bar::ONE_QUARTER,
-            f if f >= 1.0 / 8.0 => ratatui::symbols::bar::ONE_EIGHTH,
-            _ => "",
-        };
+    fn render(&self, area: Rect, width: u16, buf: &mut Buffer, rta: &RTA, force_visible: bool) {
+        if area.is_empty() {
+            return;
+        }
+        let value = rta.band_ratio(self);
+        let scaled_eighths = value * area.height as f32 * 8.0;
+        let max_eighths = area.height as u32 * 8;
+        let width = width.min(area.width);
 
-        for i in 0..full_blocks {
-            for x in 0..width {
+        for x in 0..width {
+            let mut eighths = match rta.partial_rounding {
+                PartialRounding::Floor => scaled_eighths.floor(),
+                PartialRounding::Nearest => scaled_eighths.round(),
+                PartialRounding::Ceil => scaled_eighths.ceil(),
+                PartialRounding::Dithered => {
+                    (scaled_eighths + DITHER_OFFSETS[x as usize % DITHER_OFFSETS.len()]).floor()
+                }
+            } as u32;
+            eighths = eighths.min(max_eighths);
+            if force_visible && value > 0.0 {
+                eighths = eighths.max(1);
+            }
+
+            if rta.bar_style == BarStyle::Outline {
+                // Outlines can't show fractions, so the top cap is drawn in the partial cell.
+                self.render_outline(area, width, buf, eighths.div_ceil(8) as u16);
+                return;
+            }
+
+            if let Some(colors) = rta.channel_colors.as_deref()
+                && !self.channel_energies.is_empty()
+            {
+                let patterns = rta.color_mode == ColorMode::Monochrome;
+                self.render_stacked(area, x, eighths, colors, patterns, buf);
+                continue;
+            }
+
+            let full_blocks = (eighths / 8) as u16;
+            let partial_block = PARTIAL_BLOCKS[(eighths % 8) as usize];
+
+            for i in 0..full_blocks {
                 buf[(area.left() + x, area.bottom().saturating_sub(i + 1))]
                     .set_fg(self.color)
                     .set_symbol(ratatui::symbols::bar::FULL);
             }
-        }
-        if !partial_block.is_empty() {
-            let partial_y = area.bottom().saturating_sub(full_blocks + 1);
-            for x in 0..width {
+            if !partial_block.is_empty() {
+                let partial_y = area.bottom().saturating_sub(full_blocks + 1);
                 buf[(area.left() + x, partial_y)]
                     .set_fg(self.color)
                     .set_symbol(partial_block);
             }
         }
     }
+
+    /// Draws one column of the bar, `eighths` high, partitioned by channel energy share.
+    ///
+    /// With `patterns`, full cells are filled with the pattern of the channel covering most of
+    /// them instead of being colored.
+    fn render_stacked(
+        &self,
+        area: Rect,
+        x: u16,
+        eighths: u32,
+        colors: &[Color],
+        patterns: bool,
+        buf: &mut Buffer,
+    ) {
+        let total: f32 = self.channel_energies.iter().map(|e| e.max(0.0)).sum();
+        if total <= 0.0 || !total.is_finite() {
+            return;
+        }
+        // The eighth at which each channel's segment ends.
+        let mut cumulative = 0.0;
+        let ends: Vec<u32> = self
+            .channel_energies
+            .iter()
+            .map(|e| {
+                cumulative += e.max(0.0) / total;
+                (cumulative * eighths as f32).round() as u32
+            })
+            .collect();
+        let channel_at = |eighth: u32| ends.iter().position(|&end| eighth < end).unwrap_or(0);
+        let color_at = |eighth: u32| colors[channel_at(eighth) % colors.len()];
+
+        for row in 0..eighths.div_ceil(8) {
+            let (bottom, top) = (row * 8, (row * 8 + 8).min(eighths));
+            let y = area.bottom() - 1 - row as u16;
+            let lower = color_at(bottom);
+            // The number of eighths in the cell that belong to the bottom channel.
+            let lower_eighths = (bottom..top).take_while(|&e| color_at(e) == lower).count();
+            let filled = (top - bottom) as usize;
+            let cell = &mut buf[(area.left() + x, y)];
+            if patterns && filled == 8 {
+                let channel = channel_at(bottom + 4);
+                cell.set_symbol(CHANNEL_PATTERNS[channel % CHANNEL_PATTERNS.len()]);
+            } else if lower_eighths < filled && filled == 8 {
+                cell.set_fg(lower)
+                    .set_bg(color_at(top - 1))
+                    .set_symbol(PARTIAL_BLOCKS[lower_eighths]);
+            } else {
+                // A partially filled top cell can only show one color.
+                let symbol = PARTIAL_BLOCKS
+                    .get(filled)
+                    .copied()
+                    .unwrap_or(ratatui::symbols::bar::FULL);
+                cell.set_fg(lower).set_symbol(symbol);
+            }
+        }
+    }
+
+    /// Draws the bar as a top cap with side edges, `rows` high, leaving the inside untouched.
+    fn render_outline(&self, area: Rect, width: u16, buf: &mut Buffer, rows: u16) {
+        use ratatui::symbols::line;
+
+        if rows == 0 || width == 0 {
+            return;
+        }
+        let top = area.bottom() - rows;
+        let (left, right) = (area.left(), area.left() + width - 1);
+        for y in top..area.bottom() {
+            let symbols = if y == top {
+                (line::TOP_LEFT, line::TOP_RIGHT)
+            } else {
+                (line::VERTICAL, line::VERTICAL)
+            };
+            buf[(left, y)].set_fg(self.color).set_symbol(symbols.0);
+            buf[(right, y)].set_fg(self.color).set_symbol(symbols.1);
+        }
+        for x in left + 1..right {
+            buf[(x, top)]
+                .set_fg(self.color)
+                .set_symbol(line::HORIZONTAL);
+        }
+        if width == 1 {
+            buf[(left, top)]
+                .set_fg(self.color)
+                .set_symbol(line::HORIZONTAL_DOWN);
+        }
+    }
+}
+
+/// The areas of the parts of an `RTA` meter.
+pub(crate) struct MeterLayout {
+    /// The area of the peak labels, if shown above the meter.
+    peak_labels: Option<Rect>,
+    /// The area of the peak readout, if shown in the title line of the block.
+    peak_title: Option<Rect>,
+    /// The area of the top ruler, if shown.
+    top_ruler: Option<Rect>,
+    /// The area of the dB axis labels.
+    db_axis: Rect,
+    /// The area of the axis lines, around the bars.
+    axis: Rect,
+    /// The area of the frequency labels, below the bars.
+    freq_axis: Rect,
+    /// The area of the piano keyboard, if shown.
+    keyboard: Option<Rect>,
+    /// The area of the footer, if any.
+    footer: Option<Rect>,
+    /// The areas of the bars.
+    geometry: RtaGeometry,
+}
+
+/// The rendered chrome of an `RTA` meter, reused while the size and configuration are unchanged.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChromeCache {
+    /// A hash of everything the chrome depends on, or `None` if nothing was cached yet.
+    key: Option<u64>,
+    /// The rendered chrome.
+    buffer: Buffer,
+}
+
+impl ChromeCache {
+    /// Renders the chrome of `rta` into `buf`, from the cache if it is still valid. Returns whether
+    /// the cache was used.
+    fn render(&mut self, rta: &RTA, area: Rect, layout: &MeterLayout, buf: &mut Buffer) -> bool {
+        let key = Self::key(rta, area);
+        let cached = self.key == Some(key);
+        if !cached {
+            self.buffer = Buffer::empty(area);
+            rta.render_chrome(area, layout, &mut self.buffer);
+            self.key = Some(key);
+        }
+
+        let empty = Cell::default();
+        for (i, cell) in self.buffer.content.iter().enumerate() {
+            if *cell == empty {
+                continue;
+            }
+            let (x, y) = self.buffer.pos_of(i);
+            if buf.area.contains(Position::new(x, y)) {
+                buf[(x, y)] = cell.clone();
+            }
+        }
+        cached
+    }
+
+    fn key(rta: &RTA, area: Rect) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        area.hash(&mut hasher);
+        rta.block.hash(&mut hasher);
+        rta.padding.hash(&mut hasher);
+        rta.show_peak_labels.hash(&mut hasher);
+        rta.peak_labels_in_title.hash(&mut hasher);
+        rta.min_db.to_bits().hash(&mut hasher);
+        rta.display_scale.hash(&mut hasher);
+        rta.frequency_axis.hash(&mut hasher);
+        rta.axis_style.hash(&mut hasher);
+        rta.piano_keyboard.hash(&mut hasher);
+        rta.footer.is_some().hash(&mut hasher);
+        rta.top_ruler.hash(&mut hasher);
+        rta.color_mode.hash(&mut hasher);
+        rta.number_format.hash(&mut hasher);
+        for band in &rta.bands {
+            band.frequency.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
 }
 
 impl<'a> Widget for RTA<'a> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        if let Some(block) = self.block.as_ref() {
-            block.render(area, buf);
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(bands = self.bands.len()))
+    )]
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        self.apply_comparison();
+        self.apply_theme_bars();
+        self.apply_peak_highlight();
+        self.render_meter(area, buf);
+        self.apply_theme(area, buf);
+        self.apply_color_mode(area, buf);
+    }
+}
+
+impl<'a> StatefulWidget for RTA<'a> {
+    type State = RTAState;
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(bands = self.bands.len()))
+    )]
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let started_at = state.collects_render_stats().then(Instant::now);
+        state.start_activity_timer();
+        self.apply_comparison();
+        if let Some(timeout) = self.ambient_timeout
+            && state.is_inactive(timeout)
+        {
+            let targets: Vec<f32> = self
+                .bands
+                .iter()
+                .map(|band| self.band_ratio(band))
+                .collect();
+            Self::render_ambient(state.smooth_ambient(&targets), area, buf);
+            self.apply_color_mode(area, buf);
+            state.geometry = None;
+            return;
+        }
+        if let Some(theme) = state.current_theme() {
+            self.theme = Some(theme);
+        }
+        self.apply_theme_bars();
+        let threshold_reached = self.alert_threshold_db.is_some_and(|threshold_db| {
+            self.bands
+                .iter()
+                .any(|band| band.get_db(self.min_db) >= threshold_db)
+        });
+        if threshold_reached {
+            state.trigger_alert();
+        }
+        if state.is_alerting(self.alert_hold) {
+            let alert_style = match self.alert_blink {
+                AlertBlink::Off => Some(self.alert_style),
+                AlertBlink::Slow => Some(self.alert_style.add_modifier(Modifier::SLOW_BLINK)),
+                AlertBlink::Rapid => Some(self.alert_style.add_modifier(Modifier::RAPID_BLINK)),
+                AlertBlink::Software(period) => {
+                    state.blink_visible(period).then_some(self.alert_style)
+                }
+            };
+            if let Some(alert_style) = alert_style {
+                self.block = self.block.map(|block| block.border_style(alert_style));
+            }
         }
 
+        let idle = self.idle_threshold_db.is_some_and(|threshold_db| {
+            state.update_silence(
+                self.bands
+                    .iter()
+                    .all(|band| band.get_db(self.min_db) < threshold_db),
+            );
+            state.is_idle(self.idle_timeout)
+        });
+        state.update_band_maxima(self.bands.iter().map(|band| {
+            if band.value.is_nan() {
+                f32::NAN
+            } else {
+                band.get_db(self.min_db)
+            }
+        }));
+        if self.show_band_maxima {
+            self.band_maxima = state.band_maxima().to_vec();
+        }
+        if self.peak_dots > 0 {
+            let levels: Vec<f32> = self
+                .bands
+                .iter()
+                .map(|band| {
+                    if band.value.is_nan() {
+                        f32::NAN
+                    } else {
+                        band.get_db(self.min_db)
+                    }
+                })
+                .collect();
+            state.update_peak_dots(&levels, self.peak_dots);
+            self.peak_history = state.peak_dots();
+        }
+        self.held_peak_band = state.update_peak_band(self.loudest_band(), self.bands.len());
+        self.apply_peak_highlight();
+        if let Some(db) = state.selected_db() {
+            self.reference_level_db = Some(db);
+        }
+        if self.top_ruler == TopRuler::SessionTime {
+            self.session_time = Some(state.session_time());
+        }
+        let idle_style = self.idle_style;
+        let inner = self.block.inner_if_some(area);
+
+        let mut chrome_cached = false;
+        state.geometry = match self.layout(area) {
+            Some(layout) => {
+                chrome_cached = state.chrome.render(&self, area, &layout, buf);
+                self.render_content(&layout, buf);
+                Some(layout.geometry)
+            }
+            None => {
+                self.render_meter(area, buf);
+                None
+            }
+        };
+
+        if idle {
+            buf.set_style(area, idle_style);
+            let note_area = Rect {
+                y: inner.y + inner.height / 2,
+                height: inner.height.min(1),
+                ..inner
+            };
+            Paragraph::new("No signal")
+                .alignment(Alignment::Center)
+                .render(note_area, buf);
+        }
+        self.apply_theme(area, buf);
+        self.apply_color_mode(area, buf);
+
+        if let Some(started_at) = started_at {
+            let empty = Cell::default();
+            state.render_stats = Some(RenderStats {
+                duration: started_at.elapsed(),
+                cells_drawn: area
+                    .intersection(buf.area)
+                    .positions()
+                    .filter(|&position| buf[position] != empty)
+                    .count(),
+                bands_rendered: state.geometry.as_ref().map_or(0, |geometry| {
+                    geometry
+                        .band_areas()
+                        .iter()
+                        .filter(|area| !area.is_empty())
+                        .count()
+                }),
+                chrome_cached,
+            });
+        }
+    }
+}
+
+impl RTA<'_> {
+    /// Renders the meter.
+    fn render_meter(&self, area: Rect, buf: &mut Buffer) {
+        let Some(layout) = self.layout(area) else {
+            if let Some(block) = self.block.as_ref() {
+                block.render(area, buf);
+            }
+            return;
+        };
+        self.render_chrome(area, &layout, buf);
+        self.render_content(&layout, buf);
+    }
+
+    /// Where the bars would be drawn when rendering in `area`, and how frequencies and levels map
+    /// to cells, or `None` if there is no room for the meter.
+    pub fn geometry(&self, area: Rect) -> Option<RtaGeometry> {
+        self.layout(area).map(|layout| layout.geometry)
+    }
+
+    /// Draws bars of the given heights across `area` in rainbow colors, with a gap between bars
+    /// where there is room.
+    fn render_ambient(values: &[f32], area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        let count = values.len() as u32;
+        if area.is_empty() || count == 0 {
+            return;
+        }
+        let width = area.width as u32;
+        let last = (count - 1).max(1) as f32;
+        for (i, &value) in values.iter().enumerate() {
+            let left = area.x as u32 + i as u32 * width / count;
+            let right = area.x as u32 + (i as u32 + 1) * width / count;
+            let right = if right - left > 1 { right - 1 } else { right };
+            let color = gradient_color(&RAINBOW, i as f32 / last);
+            let eighths = (value * area.height as f32 * 8.0) as u16;
+            for x in left as u16..right as u16 {
+                for row in 0..eighths / 8 {
+                    buf[(x, area.bottom() - 1 - row)]
+                        .set_fg(color)
+                        .set_symbol(ratatui::symbols::bar::FULL);
+                }
+                let partial = PARTIAL_BLOCKS[(eighths % 8) as usize];
+                if !partial.is_empty() {
+                    buf[(x, area.bottom() - 1 - eighths / 8)]
+                        .set_fg(color)
+                        .set_symbol(partial);
+                }
+            }
+        }
+    }
+
+    /// Colors all bands with the bar color of the theme, if it has one.
+    fn apply_theme_bars(&mut self) {
+        if let Some(color) = self.theme.and_then(|theme| theme.bars) {
+            for band in &mut self.bands {
+                band.color = color;
+            }
+        }
+    }
+
+    /// Recolors the background and the default and white text drawn in `area` for the theme,
+    /// leaving the piano keyboard as is.
+    fn apply_theme(&self, area: Rect, buf: &mut Buffer) {
+        let Some(theme) = self.theme else {
+            return;
+        };
+        let keyboard = self
+            .layout(area)
+            .and_then(|layout| layout.keyboard)
+            .unwrap_or_default();
+        for position in area.intersection(buf.area).positions() {
+            if keyboard.contains(position) {
+                continue;
+            }
+            let cell = &mut buf[position];
+            if cell.bg == Color::Reset {
+                cell.bg = theme.background;
+            }
+            if matches!(cell.fg, Color::Reset | Color::White) {
+                cell.fg = theme.foreground;
+            }
+        }
+    }
+
+    /// Recolors everything drawn in `area` for the color mode.
+    fn apply_color_mode(&self, area: Rect, buf: &mut Buffer) {
+        if self.color_mode == ColorMode::Color {
+            return;
+        }
+        for position in area.intersection(buf.area).positions() {
+            let cell = &mut buf[position];
+            match self.color_mode {
+                ColorMode::Color => {}
+                ColorMode::HighContrast => {
+                    cell.fg = high_contrast(cell.fg);
+                    if cell.bg == Color::Reset {
+                        cell.bg = Color::Black;
+                    }
+                    cell.modifier.remove(Modifier::DIM);
+                }
+                ColorMode::Monochrome => {
+                    // Keep cells drawn with a light background, like labels, stand out.
+                    if matches!(cell.bg, Color::White | Color::Gray) {
+                        cell.modifier.insert(Modifier::REVERSED);
+                    }
+                    cell.fg = Color::Reset;
+                    cell.bg = Color::Reset;
+                }
+            }
+        }
+    }
+
+    /// Splits `area` into the areas of the parts of the meter, or `None` if there is no room.
+    fn layout(&self, area: Rect) -> Option<MeterLayout> {
         let mut rta_area = self.block.inner_if_some(area);
+        // Without a top border there is no title line to put the readout in.
+        let has_title_line = rta_area.y > area.y;
+        rta_area = Block::new().padding(self.padding).inner(rta_area);
         if rta_area.is_empty() {
-            return;
+            return None;
         }
 
-        if self.show_peak_labels {
+        let mut peak_labels = None;
+        let mut peak_title = None;
+        if self.show_peak_labels && self.peak_labels_in_title && has_title_line {
+            peak_title = Some(Rect {
+                y: area.y,
+                height: 1,
+                ..rta_area
+            });
+        } else if self.show_peak_labels {
             let [top_area, rest] =
                 Layout::vertical([Constraint::Length(2), Constraint::Fill(0)]).areas(rta_area);
-            self.render_peak_labels(top_area, buf);
+            peak_labels = Some(top_area);
+            rta_area = rest;
+        }
+
+        let mut top_ruler = None;
+        if self.top_ruler != TopRuler::None {
+            let [ruler_area, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(0)]).areas(rta_area);
+            top_ruler = Some(ruler_area);
             rta_area = rest;
         }
 
-        let db_axis_width = if self.min_db > -100.0 { 3 } else { 4 };
+        let db_axis_width = if self.min_db > -100.0 && self.display_scale == DisplayScale::Decibel {
+            3
+        } else {
+            4
+        };
 
         // left_area is the dB axis, right_area holds the RTA area and the frequency axis.
         let [left_area, right_area] =
             Layout::horizontal([Constraint::Length(db_axis_width), Constraint::Fill(0)])
                 .areas(rta_area);
 
-        // db axis must start one block above the bottom to align with frequency axis.
-        let [db_axis, _] =
-            Layout::vertical([Constraint::Fill(0), Constraint::Length(2)]).areas(left_area);
+        let keyboard_height = u16::from(self.piano_keyboard);
+        let footer_height = u16::from(self.footer.is_some());
 
-        let [rta_area, freq_axis] =
-            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(right_area);
+        let borders = self.axis_style.borders;
+        let [top, bottom, left, right] =
+            [Borders::TOP, Borders::BOTTOM, Borders::LEFT, Borders::RIGHT]
+                .map(|side| u16::from(borders.contains(side)));
 
-        let num_bands = self.bands.len() as u16;
-        if num_bands == 0 {
-            panic!("No bands configured — cannot continue");
+        // db axis must span the rows of the bars, between the axis lines above and below them.
+        let [_, db_axis, _] = Layout::vertical([
+            Constraint::Length(top),
+            Constraint::Fill(0),
+            Constraint::Length(1 + bottom + keyboard_height + footer_height),
+        ])
+        .areas(left_area);
+
+        let [rta_area, freq_axis, keyboard_area, footer_area] = Layout::vertical([
+            Constraint::Fill(0),
+            Constraint::Length(1),
+            Constraint::Length(keyboard_height),
+            Constraint::Length(footer_height),
+        ])
+        .areas(right_area);
+
+        // Bands that don't fit get a zero-width area and are not drawn.
+        let num_bands = u16::try_from(self.bands.len()).unwrap_or(u16::MAX);
+        if num_bands == 0 || rta_area.width < 2 {
+            return None;
         }
 
         // The min bar_width is 1
-        let bar_width = ((rta_area.width - 1) / num_bands).clamp(1, rta_area.width);
+        let bar_width = (rta_area.width.saturating_sub(left + right) / num_bands).max(1);
+        let bands_area_width = bar_width.saturating_mul(num_bands);
 
-        let axis = Block::default()
-            .borders(Borders::LEFT | Borders::BOTTOM)
-            .border_style(Color::White);
+        // Render the axis lines only as wide as the bars area
+        let axis = Rect {
+            width: bands_area_width
+                .saturating_add(left + right)
+                .min(rta_area.width),
+            ..rta_area
+        };
+        let bands_area = self.axis().inner(axis);
 
-        let bands_area = axis.inner(rta_area);
-        let bands_area_width = bar_width * num_bands;
+        // All bars are equally wide, so there is no need for the layout solver, which is slow
+        // with hundreds of bands.
+        let bands = (0..num_bands)
+            .map(|i| {
+                let x = (i as u32 * bar_width as u32).min(bands_area.width as u32) as u16;
+                Rect {
+                    x: bands_area.x + x,
+                    width: bar_width.min(bands_area.width - x),
+                    ..bands_area
+                }
+            })
+            .collect();
 
-        // Render the x-axis and frequency labels only as wide as the bars area
-        axis.render(
-            Rect {
-                width: bands_area_width + 1,
-                ..rta_area
+        Some(MeterLayout {
+            peak_labels,
+            peak_title,
+            // Right-align the ruler with the bars
+            top_ruler: top_ruler.map(|ruler_area| Rect {
+                x: bands_area.x,
+                width: bands_area.width,
+                ..ruler_area
+            }),
+            db_axis,
+            axis,
+            freq_axis: Rect {
+                x: bands_area.x,
+                width: bands_area_width.min(bands_area.width),
+                ..freq_axis
             },
-            buf,
-        );
+            keyboard: self.piano_keyboard.then_some(Rect {
+                x: bands_area.x,
+                width: bands_area_width.min(bands_area.width),
+                ..keyboard_area
+            }),
+            footer: self.footer.is_some().then_some(Rect {
+                x: bands_area.x,
+                width: bands_area_width.min(bands_area.width),
+                ..footer_area
+            }),
+            geometry: RtaGeometry::new(
+                &self.bands,
+                bands,
+                Rect {
+                    width: bands_area_width.min(bands_area.width),
+                    ..bands_area
+                },
+                bar_width,
+                self.min_db,
+                self.display_scale,
+            ),
+        })
+    }
+
+    /// The axis lines along the bars.
+    fn axis(&self) -> Block<'static> {
+        Block::default()
+            .borders(self.axis_style.borders)
+            .border_set(self.axis_style.border_set)
+            .border_style(self.axis_style.style)
+    }
+
+    /// Renders the parts of the meter that don't depend on the band values: the block, the axes
+    /// and their labels.
+    fn render_chrome(&self, area: Rect, layout: &MeterLayout, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+        self.axis().render(layout.axis, buf);
+        self.render_db_scale(layout.db_axis, buf);
+        self.render_freq_scale(layout.freq_axis, layout.geometry.bar_width(), buf);
+        if let Some(keyboard_area) = layout.keyboard {
+            self.render_piano_keyboard(keyboard_area, &layout.geometry, buf);
+        }
+    }
 
-        let rta_bands = Layout::horizontal(vec![Constraint::Length(bar_width); num_bands as usize])
-            .split(bands_area);
+    /// Renders the parts of the meter that change with the band values.
+    fn render_content(&self, layout: &MeterLayout, buf: &mut Buffer) {
+        if let Some(peak_labels) = layout.peak_labels {
+            self.render_peak_labels(peak_labels, buf);
+        }
+        if let Some(peak_title) = layout.peak_title {
+            self.render_peak_title(peak_title, buf);
+        }
+        if let (Some(area), Some(label)) = (layout.top_ruler, self.top_ruler_label()) {
+            Paragraph::new(label)
+                .alignment(Alignment::Right)
+                .render(area, buf);
+        }
 
-        self.render_db_scale(db_axis, buf);
+        for (index, (band, area)) in zip(&self.bands, layout.geometry.band_areas()).enumerate() {
+            band.render(
+                *area,
+                layout.geometry.bar_width(),
+                buf,
+                self,
+                self.min_visible
+                    && self
+                        .min_visible_gate_db
+                        .is_none_or(|gate_db| band.get_db(self.min_db) > gate_db),
+            );
+            if let Some(style) = self.emphasis_style(band) {
+                buf.set_style(*area, style);
+            }
+            if let Some(style_fn) = &self.band_style_fn {
+                buf.set_style(*area, (style_fn.0)(index, band));
+            }
+            if self.flag_invalid_bands && (band.invalid || band.value.is_nan()) && !area.is_empty()
+            {
+                buf[(area.left(), area.bottom() - 1)]
+                    .set_symbol("!")
+                    .set_fg(Color::Red);
+            }
+        }
 
-        let freq_axis = Rect {
-            width: bands_area_width + 1,
-            ..freq_axis
-        };
-        self.render_freq_scale(freq_axis, bar_width, buf);
+        self.render_comparison(&layout.geometry, buf);
+        self.render_sidechain(&layout.geometry, buf);
+
+        if let Some(style) = self.error_bars {
+            self.render_error_bars(style, layout.geometry.band_areas(), buf);
+        }
+
+        self.render_reference_curves(layout.geometry.band_areas(), buf);
+        self.render_masking_curve(layout.geometry.band_areas(), buf);
 
-        for (band, area) in zip(self.bands, rta_bands.iter()) {
-            band.render(*area, bar_width, buf);
+        for markers in &self.markers {
+            for (&db, area) in zip(markers.values, layout.geometry.band_areas()) {
+                let Some(y) = layout.geometry.row_of(db) else {
+                    continue;
+                };
+                for x in area.left()..area.right() {
+                    buf[(x, y)]
+                        .set_fg(markers.color)
+                        .set_symbol(ratatui::symbols::line::THICK_HORIZONTAL);
+                }
+            }
+        }
+
+        if let Some(db) = self.reference_level_db {
+            self.render_reference_level(db, layout.geometry.bars_area(), buf);
+        }
+
+        if self.peak_dots > 0 {
+            self.render_peak_dots(&layout.geometry, buf);
+        }
+
+        if self.show_band_maxima {
+            self.render_band_maxima(&layout.geometry, buf);
+        }
+
+        if let (Some(footer), Some(area)) = (self.footer.as_ref(), layout.footer) {
+            footer.render(area, buf);
+        }
+
+        if !self.overlays.is_empty() {
+            let ctx = RtaRenderContext {
+                bands: &self.bands,
+                geometry: &layout.geometry,
+            };
+            for overlay in &self.overlays {
+                overlay.0.render(&ctx, buf);
+            }
         }
     }
-}
 
-impl RTA<'_> {
     fn render_db_scale(&self, area: Rect, buf: &mut Buffer) {
         // Render a label for each 3rd line
-        let num_labels = (area.height as u32) / 3;
+        let num_labels = area.height / 3;
 
         let layout = Layout::vertical(vec![
-            Constraint::Ratio(1, num_labels);
-            num_labels.try_into().unwrap()
+            Constraint::Ratio(1, num_labels.into());
+            num_labels.into()
         ]);
         let label_areas = layout.split(area);
 
         let label_value_delta = -self.min_db / num_labels as f32;
 
         for (i, label_area) in label_areas.iter().enumerate() {
-            let db_value = 0.0 - (label_value_delta * i as f32);
-            let label_text = format!("{:.0}", db_value);
+            let label_text = match self.display_scale {
+                DisplayScale::Decibel => {
+                    let db_value = 0.0 - (label_value_delta * i as f32);
+                    format!("{:.0}", db_value)
+                }
+                DisplayScale::Percent => {
+                    format!("{:.0}%", 100.0 * (1.0 - i as f32 / num_labels as f32))
+                }
+            };
             Paragraph::new(label_text)
                 .alignment(Alignment::Right)
                 .render(*label_area, buf);
         }
     }
 
-    fn format_frequency_label(freq: u16) -> String {
-        if freq >= 10000 {
-            let label = format!("{:.0}", freq as f64 / 1000.0);
-            format!("{}k", label)
-        } else if freq >= 1000 {
-            let label = format!("{:.1}", freq as f64 / 1000.0);
-            if label.ends_with(".0") {
-                format!("{}k", label.trim_end_matches(".0"))
-            } else {
-                format!("{}k", label)
+    fn format_frequency_label(&self, freq: u16) -> String {
+        if self.frequency_axis == FrequencyAxis::Note {
+            if freq == 0 {
+                return String::new();
             }
-        } else {
-            format!("{}", freq)
+            return note_name(frequency_to_midi(freq as f32).round() as i32);
         }
+        self.number_format.frequency(freq)
     }
 
     fn render_freq_scale(&self, area: Rect, bar_width: u16, buf: &mut Buffer) {
-        // skip the first char position where the dB axis starts
-        let [_, label_area] =
-            Layout::horizontal([Constraint::Length(1), Constraint::Fill(0)]).areas(area);
-
         // Decide the spacing between labels based on the bar width.
-        let label_spacing_bars = if bar_width > 3 {
+        let label_spacing_bars: u16 = if bar_width > 3 {
             2
         } else if bar_width > 2 {
             4
@@ -170,54 +853,426 @@ impl RTA<'_> {
             6
         };
 
-        let label_width = label_spacing_bars * bar_width;
-        let num_labels = (label_area.width - (label_spacing_bars * bar_width).max(9)) / label_width;
+        // Bands that don't fit are clipped on the right and get no labels.
+        let visible = self.bands.len().min((area.width / bar_width) as usize);
+        let bands = &self.bands[..visible];
+
+        let label_width = label_spacing_bars.saturating_mul(bar_width);
+        let num_labels = area.width.saturating_sub(label_width.max(9)) / label_width;
+        // Never label more bands than there are.
+        let num_labels =
+            (num_labels as usize).min(bands.len().div_ceil(label_spacing_bars as usize));
 
-        let mut constraints = vec![Constraint::Length(label_width); num_labels as usize];
+        let mut constraints = vec![Constraint::Length(label_width); num_labels];
         constraints.push(Constraint::Fill(0));
 
-        let labels_area = Layout::horizontal(constraints).split(label_area);
+        let labels_area = Layout::horizontal(constraints).split(area);
 
         for (i, label_area) in labels_area.iter().enumerate() {
             let band_index = i * label_spacing_bars as usize;
-            let freq = self.bands[band_index].frequency.unwrap_or(0);
-            Paragraph::new(Self::format_frequency_label(freq))
+            let Some(band) = bands.get(band_index) else {
+                break;
+            };
+            Paragraph::new(self.format_frequency_label(band.frequency.unwrap_or(0)))
                 .alignment(Alignment::Left)
                 .render(*label_area, buf);
         }
 
         // Render the last label on the right side of the last area.
-        let freq = self.bands[self.bands.len() - 1].frequency.unwrap_or(0);
-        Paragraph::new(Self::format_frequency_label(freq))
-            .alignment(Alignment::Right)
-            .render(labels_area[labels_area.len() - 1], buf);
+        if let (Some(band), Some(last_area)) = (bands.last(), labels_area.last()) {
+            Paragraph::new(self.format_frequency_label(band.frequency.unwrap_or(0)))
+                .alignment(Alignment::Right)
+                .render(*last_area, buf);
+        }
     }
 
-    /// Get a clone of the band with the highest value.
-    fn get_peak_band(&self) -> Option<Band> {
+    /// Draw a one-row piano keyboard aligned with the bands.
+    ///
+    /// Columns within a single key show it as white or black, columns spanning several keys are
+    /// shaded, and columns containing a C show its octave number.
+    fn render_piano_keyboard(&self, area: Rect, geometry: &RtaGeometry, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        for x in 0..area.width {
+            let note_at = |column: f32| geometry.frequency_at_offset(column).map(frequency_to_midi);
+            let (Some(left), Some(right)) = (note_at(x as f32), note_at(x as f32 + 1.0)) else {
+                return;
+            };
+            let cell = &mut buf[(area.left() + x, area.top())];
+            // The first C whose exact pitch lies within the column.
+            let c = (left.ceil() as i32..right.ceil() as i32).find(|note| note.rem_euclid(12) == 0);
+            if let Some(c) = c {
+                let octave = c.div_euclid(12) - 1;
+                let label = u32::try_from(octave)
+                    .ok()
+                    .and_then(|octave| char::from_digit(octave, 10))
+                    .unwrap_or('C');
+                cell.set_char(label)
+                    .set_fg(Color::Black)
+                    .set_bg(Color::White);
+            } else if right - left <= 1.0 {
+                let note = ((left + right) / 2.0).round() as i32;
+                let black = NOTE_NAMES[note.rem_euclid(12) as usize].ends_with('#');
+                let symbol = if black && self.color_mode == ColorMode::Monochrome {
+                    " "
+                } else {
+                    ratatui::symbols::block::FULL
+                };
+                cell.set_symbol(symbol)
+                    .set_fg(if black { Color::DarkGray } else { Color::White });
+            } else {
+                cell.set_symbol(ratatui::symbols::shade::MEDIUM)
+                    .set_fg(Color::White);
+            }
+        }
+    }
+
+    /// Draw each reference curve as a line segment at its level in every band it covers.
+    fn render_reference_curves(&self, band_areas: &[Rect], buf: &mut Buffer) {
+        for curve in &self.reference_curves {
+            for (db, area) in zip(resample_curve(curve.points, &self.bands), band_areas) {
+                self.render_curve_segment(db, *area, curve.color, buf);
+            }
+        }
+    }
+
+    /// Draws the estimated masking threshold as a curve, if enabled.
+    fn render_masking_curve(&self, band_areas: &[Rect], buf: &mut Buffer) {
+        let Some(color) = self.masking_curve else {
+            return;
+        };
+        for (db, area) in zip(masking_threshold(&self.bands, self.min_db), band_areas) {
+            self.render_curve_segment(db, *area, color, buf);
+        }
+    }
+
+    /// Draws the segment of a curve at `db` across the band in `area`.
+    fn render_curve_segment(&self, db: f32, area: Rect, color: Color, buf: &mut Buffer) {
+        if db.is_nan() || db < self.min_db || area.height == 0 {
+            return;
+        }
+        let value = self.display_scale.ratio(db, self.min_db);
+        let level = value * area.height as f32;
+        let row = (level as u16).min(area.height - 1);
+        let y = area.bottom() - 1 - row;
+        for x in area.left()..area.right() {
+            buf[(x, y)]
+                .set_fg(color)
+                .set_symbol(ratatui::symbols::line::HORIZONTAL);
+        }
+        if self.anti_aliased_curves {
+            Self::render_curve_shade(level - (row as f32 + 0.5), y, area, color, buf);
+        }
+    }
+
+    /// Shades the empty cells next to a curve drawn in row `y`, darker the closer the curve is to
+    /// them. `offset` is how far the curve is above the middle of its row, in rows.
+    fn render_curve_shade(offset: f32, y: u16, area: Rect, color: Color, buf: &mut Buffer) {
+        let symbol = match offset.abs() * 2.0 {
+            coverage if coverage > 0.75 => ratatui::symbols::shade::DARK,
+            coverage if coverage > 0.45 => ratatui::symbols::shade::MEDIUM,
+            coverage if coverage > 0.15 => ratatui::symbols::shade::LIGHT,
+            _ => return,
+        };
+        let neighbor = if offset > 0.0 {
+            y.checked_sub(1).filter(|&y| y >= area.top())
+        } else {
+            Some(y + 1).filter(|&y| y < area.bottom())
+        };
+        let Some(y) = neighbor else {
+            return;
+        };
+        for x in area.left()..area.right() {
+            let cell = &mut buf[(x, y)];
+            // Don't draw over the bars.
+            if cell.symbol() == " " {
+                cell.set_fg(color).set_symbol(symbol);
+            }
+        }
+    }
+
+    /// Draws the recent peaks of each band as dots above its bar, fading with age.
+    fn render_peak_dots(&self, geometry: &RtaGeometry, buf: &mut Buffer) {
+        for ((band, peaks), area) in
+            zip(zip(&self.bands, &self.peak_history), geometry.band_areas())
+        {
+            let bar_top = geometry.row_of(band.get_db(self.min_db));
+            // Draw the oldest first so newer dots in the same row stay visible.
+            for (age, &db) in peaks.iter().enumerate().rev() {
+                let Some(y) = geometry.row_of(db) else {
+                    continue;
+                };
+                if bar_top.is_some_and(|top| y >= top) {
+                    continue;
+                }
+                let modifier = if age == 0 {
+                    Modifier::BOLD
+                } else if age * 2 >= self.peak_dots {
+                    Modifier::DIM
+                } else {
+                    Modifier::empty()
+                };
+                for x in area.left()..area.right() {
+                    let cell = &mut buf[(x, y)];
+                    cell.set_fg(band.color)
+                        .set_symbol(ratatui::symbols::line::HORIZONTAL);
+                    cell.modifier = modifier;
+                }
+            }
+        }
+    }
+
+    /// Labels each band wide enough for it with its maximum, one row above the maximum.
+    fn render_band_maxima(&self, geometry: &RtaGeometry, buf: &mut Buffer) {
+        for (&db, area) in zip(&self.band_maxima, geometry.band_areas()) {
+            let Some(row) = geometry.row_of(db) else {
+                continue;
+            };
+            let label = self.number_format.decimal(db as f64, 0);
+            if label.chars().count() > area.width as usize {
+                continue;
+            }
+            let y = row.saturating_sub(1).max(area.top());
+            buf.set_string(area.left(), y, label, Style::new().fg(Color::White));
+        }
+    }
+
+    /// Draw a dashed line with a level label across the bars at `db`.
+    fn render_reference_level(&self, db: f32, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || !db.is_finite() || db < self.min_db {
+            return;
+        }
+        let value = self.display_scale.ratio(db, self.min_db);
+        let row = ((value * area.height as f32) as u16).min(area.height - 1);
+        let y = area.bottom() - 1 - row;
+        for x in area.left()..area.right() {
+            buf[(x, y)]
+                .set_fg(Color::White)
+                .set_symbol(ratatui::symbols::line::DOUBLE_HORIZONTAL);
+        }
+        buf.set_stringn(
+            area.left(),
+            y,
+            self.display_scale.format(db, &self.number_format),
+            area.width as usize,
+            Style::new().fg(Color::Black).bg(Color::White),
+        );
+    }
+
+    /// Draw the range of one standard deviation around the value of each band.
+    fn render_error_bars(&self, style: ErrorBarStyle, band_areas: &[Rect], buf: &mut Buffer) {
+        for (band, area) in zip(&self.bands, band_areas) {
+            let Some(deviation_db) = band.deviation_db else {
+                continue;
+            };
+            if area.height == 0 || deviation_db <= 0.0 {
+                continue;
+            }
+            let db = band.get_db(self.min_db);
+            let to_ratio = |db: f32| self.display_scale.ratio(db, self.min_db);
+            let to_row =
+                |db: f32| ((to_ratio(db) * area.height as f32) as u16).min(area.height - 1);
+            let low = to_row(db - deviation_db);
+            let high = to_row(db + deviation_db);
+            let top = (to_ratio(db) * area.height as f32).ceil() as u16;
+
+            for row in low..=high {
+                let y = area.bottom() - 1 - row;
+                for x in area.left()..area.right() {
+                    let cell = &mut buf[(x, y)];
+                    match style {
+                        ErrorBarStyle::Shade if row >= top => {
+                            cell.set_fg(band.color)
+                                .set_symbol(ratatui::symbols::shade::LIGHT);
+                        }
+                        ErrorBarStyle::Shade => {
+                            cell.modifier.insert(Modifier::DIM);
+                        }
+                        ErrorBarStyle::Whisker if row == low || row == high => {
+                            cell.set_fg(Color::White)
+                                .set_symbol(ratatui::symbols::line::THICK_HORIZONTAL);
+                        }
+                        ErrorBarStyle::Whisker => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// The fraction of the full bar height `band` fills on the display scale.
+    fn band_ratio(&self, band: &Band) -> f32 {
+        let value = match &self.value_map {
+            Some(value_map) => (value_map.0)(band),
+            None => band.value,
+        };
+        let value = if value.is_nan() {
+            0.0
+        } else {
+            value.clamp(0.0, 1.0)
+        };
+        match self.display_scale {
+            DisplayScale::Decibel => value,
+            DisplayScale::Percent if value <= 0.0 => 0.0,
+            // The inverse of `Band::set_db`, which is linear in dB.
+            DisplayScale::Percent => self
+                .display_scale
+                .ratio(self.min_db * (1.0 - value), self.min_db),
+        }
+    }
+
+    /// The index of the band with the highest value, outside the peak exclusion.
+    fn loudest_band(&self) -> Option<usize> {
         self.bands
             .iter()
-            .max_by(|a, b| {
-                a.value
-                    .partial_cmp(&b.value)
-                    .unwrap_or(std::cmp::Ordering::Equal)
+            .enumerate()
+            .filter(|(_, band)| !band.value.is_nan() && !band.silent)
+            .filter(|(_, band)| {
+                let excluded = self.peak_exclusion.as_ref().zip(band.frequency);
+                !excluded.is_some_and(|(range, frequency)| range.contains(&frequency))
             })
+            .max_by(|(_, a), (_, b)| a.display_value().total_cmp(&b.display_value()))
+            .map(|(index, _)| index)
+    }
+
+    /// Get a clone of the peak band, the held one if any and otherwise the band with the highest
+    /// value.
+    fn get_peak_band(&self) -> Option<Band> {
+        self.held_peak_band
+            .or_else(|| self.loudest_band())
+            .and_then(|index| self.bands.get(index))
             .cloned()
     }
 
-    fn render_peak_labels(&self, area: Rect, buf: &mut Buffer) {
+    /// Shows the bands of input B instead of input A if comparing and only B is shown.
+    fn apply_comparison(&mut self) {
+        if let Some((bands_b, Comparison::B)) = &mut self.comparison {
+            std::mem::swap(&mut self.bands, bands_b);
+        }
+    }
+
+    /// Draws the bands of input B as outlines over the bars if comparing and both inputs are
+    /// shown.
+    fn render_comparison(&self, geometry: &RtaGeometry, buf: &mut Buffer) {
+        if let Some((bands_b, Comparison::Both)) = &self.comparison {
+            self.render_outlines(bands_b, geometry, buf);
+        }
+    }
+
+    /// Draws the side-chain bands as outlines over the bars and highlights the trigger band.
+    fn render_sidechain(&self, geometry: &RtaGeometry, buf: &mut Buffer) {
+        let Some(sidechain) = &self.sidechain else {
+            return;
+        };
+        self.render_outlines(&sidechain.bands, geometry, buf);
+        if let Some(area) = self
+            .sidechain_trigger()
+            .and_then(|index| geometry.band_areas().get(index))
+        {
+            buf.set_style(
+                *area,
+                Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            );
+        }
+    }
+
+    /// Draws `bands` as outlined bars on the meter.
+    fn render_outlines(&self, bands: &[Band], geometry: &RtaGeometry, buf: &mut Buffer) {
+        for (band, area) in zip(bands, geometry.band_areas()) {
+            let rows = (self.band_ratio(band) * area.height as f32).ceil() as u16;
+            band.render_outline(*area, geometry.bar_width().min(area.width), buf, rows);
+        }
+    }
+
+    /// Colors the peak band red if highlighting it.
+    fn apply_peak_highlight(&mut self) {
+        if !self.highlight_peak_band {
+            return;
+        }
+        if let Some(band) = self
+            .held_peak_band
+            .or_else(|| self.loudest_band())
+            .and_then(|index| self.bands.get_mut(index))
+        {
+            band.color = Color::Red;
+        }
+    }
+
+    /// The texts of the peak level and peak band readouts.
+    fn peak_readout(&self) -> (String, String) {
         let peak_band = self.get_peak_band().unwrap_or(Band::new(-60.0, 20));
         let peak_db_value = peak_band.get_db(self.min_db);
 
+        let peak_label = match self.display_scale {
+            DisplayScale::Decibel => self.number_format.db(peak_db_value, 2),
+            DisplayScale::Percent => self
+                .display_scale
+                .format(peak_db_value, &self.number_format),
+        };
+        (
+            format!("{}: {}", self.number_format.peak_label, peak_label),
+            format!(
+                "{}: {}{}",
+                self.number_format.band_label,
+                peak_band.frequency.unwrap_or(20),
+                self.number_format.hz_unit
+            ),
+        )
+    }
+
+    fn render_peak_labels(&self, area: Rect, buf: &mut Buffer) {
         let [db_label_area, band_label_area] =
             Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(area);
 
-        let peak_db_label =
-            Paragraph::new(format!("Peak: {:.2}dB", peak_db_value)).alignment(Alignment::Center);
-        let peak_band_label =
-            Paragraph::new(format!("Band: {}Hz", peak_band.frequency.unwrap_or(20)))
-                .alignment(Alignment::Center);
-        peak_db_label.render(db_label_area, buf);
-        peak_band_label.render(band_label_area, buf);
+        let (level, band) = self.peak_readout();
+        Paragraph::new(level)
+            .alignment(Alignment::Center)
+            .render(db_label_area, buf);
+        Paragraph::new(band)
+            .alignment(Alignment::Center)
+            .render(band_label_area, buf);
+    }
+
+    /// Renders the peak readout on one line, right-aligned in the title line of the block.
+    fn render_peak_title(&self, area: Rect, buf: &mut Buffer) {
+        let (level, band) = self.peak_readout();
+        Line::from(format!(" {level}  {band} "))
+            .alignment(Alignment::Right)
+            .render(area, buf);
+    }
+
+    /// The text of the top ruler, or `None` if there is nothing to show.
+    fn top_ruler_label(&self) -> Option<String> {
+        let seconds = match self.top_ruler {
+            TopRuler::None => return None,
+            TopRuler::Clock => {
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .ok()?
+                    .as_secs()
+                    % 86_400
+            }
+            TopRuler::SessionTime => self.session_time?.as_secs(),
+        };
+        Some(format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        ))
+    }
+}
+
+/// The brightest variant of `color`, so it stands out on a black background.
+fn high_contrast(color: Color) -> Color {
+    match color {
+        Color::Reset | Color::DarkGray | Color::Gray => Color::White,
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue | Color::LightBlue => Color::LightCyan,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        color => color,
     }
 }
@@ -1,4 +1,27 @@
-use ratatui::{style::Color, widgets::Block};
+use std::{
+    fmt,
+    ops::{Range, RangeInclusive},
+    rc::Rc,
+    time::Duration,
+};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Borders, Padding},
+};
+
+use crate::{
+    number_format::NumberFormat,
+    overlay::{Overlay, OverlayRef},
+    theme::{Theme, blend_color},
+    trace::{FrequencyPoint, TraceStore},
+};
+
+/// The frequencies in Hz where lossy codecs cut off or smear the highest frequencies, emphasized by
+/// [`RTA::codec_artifacts`].
+pub const CODEC_ARTIFACT_FREQUENCIES: RangeInclusive<u16> = 15000..=20000;
 
 /// A widget to display an RTA audio meter.
 ///
@@ -7,11 +30,405 @@ use ratatui::{style::Color, widgets::Block};
 pub struct RTA<'a> {
     /// The block that surrounds the RTA widget, if any.
     pub(crate) block: Option<Block<'a>>,
+    /// The empty space around the meter, inside the block.
+    pub(crate) padding: Padding,
     /// The frequency bands that make up the RTA meter.
     pub(crate) bands: Vec<Band>,
     /// Whether to show the peak labels at the top of the meter.
     pub(crate) show_peak_labels: bool,
+    /// Whether to draw the peak band in red.
+    pub(crate) highlight_peak_band: bool,
+    /// The frequencies in Hz of bands that can't be the peak band, if any.
+    pub(crate) peak_exclusion: Option<Range<u16>>,
+    /// Whether to label each band with its session maximum.
+    pub(crate) show_band_maxima: bool,
+    /// The session maximum of each band in dB, tracked by [`crate::RTAState`].
+    pub(crate) band_maxima: Vec<f32>,
+    /// The bands of a second input to compare with, and which inputs are shown, if comparing.
+    pub(crate) comparison: Option<(Vec<Band>, Comparison)>,
+    /// The colors around the bands, if themed.
+    pub(crate) theme: Option<Theme>,
+    /// The number of recent peaks drawn as dots above each bar.
+    pub(crate) peak_dots: usize,
+    /// The recent peaks of each band in dB, most recent first, tracked by [`crate::RTAState`].
+    pub(crate) peak_history: Vec<Vec<f32>>,
+    /// How long without user activity before switching to ambient mode, if enabled.
+    pub(crate) ambient_timeout: Option<Duration>,
+    /// The side-chain input shown with the bands, if any.
+    pub(crate) sidechain: Option<Sidechain>,
+    /// The band shown as the peak when held by [`crate::RTAState`], instead of the loudest band.
+    pub(crate) held_peak_band: Option<usize>,
+    /// Whether to show the peak labels in the title line of the block instead.
+    pub(crate) peak_labels_in_title: bool,
     pub min_db: f32,
+    /// Frequency responses drawn as curves on top of the bars.
+    pub(crate) reference_curves: Vec<ReferenceCurve<'a>>,
+    /// The color of the estimated masking threshold curve, if shown.
+    pub(crate) masking_curve: Option<Color>,
+    /// Per-band levels from outside the meter, drawn as markers on top of the bars.
+    pub(crate) markers: Vec<BandMarkers<'a>>,
+    /// How to draw the deviation of bands, if at all.
+    pub(crate) error_bars: Option<ErrorBarStyle>,
+    /// The level in dB at or above which a band triggers an alert, if any.
+    pub(crate) alert_threshold_db: Option<f32>,
+    /// The style patched onto the block while an alert is active.
+    pub(crate) alert_style: Style,
+    /// How long an alert stays active after it was triggered.
+    pub(crate) alert_hold: Duration,
+    /// How the alert style blinks while an alert is active.
+    pub(crate) alert_blink: AlertBlink,
+    /// The level in dB below which all bands must stay for the signal to count as absent, if any.
+    pub(crate) idle_threshold_db: Option<f32>,
+    /// How long the signal must be absent before switching to the idle style.
+    pub(crate) idle_timeout: Duration,
+    /// The style applied to the whole widget while idle.
+    pub(crate) idle_style: Style,
+    /// Whether to mark bands that were given NaN values.
+    pub(crate) flag_invalid_bands: bool,
+    /// The level in dB of a horizontal reference line drawn across the bars, if any.
+    pub(crate) reference_level_db: Option<f32>,
+    /// How levels are mapped to the height of the bars.
+    pub(crate) display_scale: DisplayScale,
+    /// How the positions on the frequency axis are labelled.
+    pub(crate) frequency_axis: FrequencyAxis,
+    /// How the axis lines along the bars are drawn.
+    pub(crate) axis_style: AxisStyle,
+    /// Whether to draw a piano keyboard below the frequency axis.
+    pub(crate) piano_keyboard: bool,
+    /// How the bars are drawn.
+    pub(crate) bar_style: BarStyle,
+    /// The colors of the channels when partitioning bars by channel, if enabled.
+    pub(crate) channel_colors: Option<Vec<Color>>,
+    /// How the fractional top of a bar is rounded to eighth blocks.
+    pub(crate) partial_rounding: PartialRounding,
+    /// Whether active bands always show at least an eighth block.
+    pub(crate) min_visible: bool,
+    /// The level in dB above which a band counts as active for `min_visible`, if any.
+    pub(crate) min_visible_gate_db: Option<f32>,
+    /// How the meter uses color.
+    pub(crate) color_mode: ColorMode,
+    /// How the numeric labels are formatted.
+    pub(crate) number_format: NumberFormat,
+    /// The line shown under the frequency axis, if any.
+    pub(crate) footer: Option<Line<'a>>,
+    /// What the row above the bars shows.
+    pub(crate) top_ruler: TopRuler,
+    /// How long the session has been running, known when rendering with state.
+    pub(crate) session_time: Option<Duration>,
+    /// Custom drawing on top of the bars.
+    pub(crate) overlays: Vec<OverlayRef<'a>>,
+    /// Whether to shade the cells next to curves to smooth them.
+    pub(crate) anti_aliased_curves: bool,
+    /// The frequencies in Hz of bands drawn bold while all others are dimmed, if any.
+    pub(crate) emphasized_frequencies: Option<RangeInclusive<u16>>,
+    /// The function styling each band at render time, if any.
+    pub(crate) band_style_fn: Option<BandStyleFn<'a>>,
+    /// The function mapping each band to the value drawn at render time, if any.
+    pub(crate) value_map: Option<ValueMap<'a>>,
+}
+
+/// How the alert style of an `RTA` blinks while an alert is active, to draw attention to clipping
+/// and threshold alarms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlertBlink {
+    /// Show the alert style steadily.
+    #[default]
+    Off,
+    /// Add the terminal's slow blink modifier to the alert style.
+    Slow,
+    /// Add the terminal's rapid blink modifier to the alert style.
+    Rapid,
+    /// Switch the alert style on and off every period, for terminals that ignore the blink
+    /// modifiers. The app must redraw at least once per period for this to show.
+    Software(Duration),
+}
+
+impl IntoIterator for RTA<'_> {
+    type Item = Band;
+    type IntoIter = std::vec::IntoIter<Band>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bands.into_iter()
+    }
+}
+
+impl<'b> IntoIterator for &'b RTA<'_> {
+    type Item = &'b Band;
+    type IntoIter = std::slice::Iter<'b, Band>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bands.iter()
+    }
+}
+
+impl<'b> IntoIterator for &'b mut RTA<'_> {
+    type Item = &'b mut Band;
+    type IntoIter = std::slice::IterMut<'b, Band>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bands.iter_mut()
+    }
+}
+
+/// How the deviation of averaged bands is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorBarStyle {
+    /// Shade the range around the top of the bar in a dimmed band color.
+    Shade,
+    /// Mark the upper and lower limit of the range with whisker lines.
+    Whisker,
+}
+
+/// How levels are mapped to the height of the bars and labelled on the axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayScale {
+    /// Bars are linear in dB, from the minimum dB value to 0 dB.
+    #[default]
+    Decibel,
+    /// Bars are linear in amplitude, from 0% to 100% of full scale.
+    Percent,
+}
+
+impl DisplayScale {
+    /// Maps a level in dB to the fraction of the full bar height.
+    pub(crate) fn ratio(self, db: f32, min_db: f32) -> f32 {
+        let ratio = match self {
+            DisplayScale::Decibel => (db - min_db) / -min_db,
+            DisplayScale::Percent => 10_f32.powf(db / 20.0),
+        };
+        if ratio.is_nan() {
+            0.0
+        } else {
+            ratio.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Maps a fraction of the full bar height back to a level in dB.
+    pub(crate) fn db(self, ratio: f32, min_db: f32) -> f32 {
+        match self {
+            DisplayScale::Decibel => min_db * (1.0 - ratio),
+            DisplayScale::Percent => (20.0 * ratio.log10()).max(min_db),
+        }
+    }
+
+    /// Formats a level in dB in the unit of the scale.
+    pub(crate) fn format(self, db: f32, number_format: &NumberFormat) -> String {
+        match self {
+            DisplayScale::Decibel => number_format.db(db, 1),
+            DisplayScale::Percent => format!("{:.0}%", 100.0 * 10_f32.powf(db / 20.0)),
+        }
+    }
+}
+
+/// How the positions on the frequency axis of an `RTA` are labelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FrequencyAxis {
+    /// Frequencies in Hz, e.g. `63`, `1k`, `16k`.
+    #[default]
+    Hertz,
+    /// The nearest musical note in scientific pitch notation, e.g. `C2`, `A4`.
+    Note,
+}
+
+/// How the axis lines along the bars of an `RTA` are drawn.
+///
+/// ```
+/// use ratatui::symbols::border;
+/// use rataudio_rta::{AxisStyle, Band, RTA};
+///
+/// let rta = RTA::new(vec![Band::new(0.5, 1000)], -60.0).axis_style(AxisStyle {
+///     border_set: border::ROUNDED,
+///     ..AxisStyle::full()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AxisStyle {
+    /// The sides of the bars that have a line.
+    pub borders: Borders,
+    /// The characters the lines are drawn with.
+    pub border_set: border::Set,
+    /// The style of the lines.
+    pub style: Style,
+}
+
+impl Default for AxisStyle {
+    fn default() -> Self {
+        Self::left_bottom()
+    }
+}
+
+impl AxisStyle {
+    /// Plain white lines on the given sides of the bars.
+    pub fn new(borders: Borders) -> Self {
+        AxisStyle {
+            borders,
+            border_set: border::PLAIN,
+            style: Style::new().fg(Color::White),
+        }
+    }
+
+    /// Lines along the left and bottom of the bars, the default.
+    pub fn left_bottom() -> Self {
+        Self::new(Borders::LEFT | Borders::BOTTOM)
+    }
+
+    /// A line along the left of the bars only.
+    pub fn left() -> Self {
+        Self::new(Borders::LEFT)
+    }
+
+    /// A line along the bottom of the bars only.
+    pub fn bottom() -> Self {
+        Self::new(Borders::BOTTOM)
+    }
+
+    /// No lines, leaving the most room for the bars.
+    pub fn none() -> Self {
+        Self::new(Borders::NONE)
+    }
+
+    /// A box around the bars.
+    pub fn full() -> Self {
+        Self::new(Borders::ALL)
+    }
+}
+
+/// What the top ruler of an `RTA` shows, see [`RTA::top_ruler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TopRuler {
+    /// No top ruler.
+    #[default]
+    None,
+    /// The time of day in UTC, as `HH:MM:SS`.
+    Clock,
+    /// The time since the start of the session as `HH:MM:SS`, e.g. of a recording. The session
+    /// starts at the first render or with [`crate::RTAState::restart_session`]. Only shown when
+    /// rendering with [`ratatui::widgets::StatefulWidget`].
+    SessionTime,
+}
+
+/// How the bars of an `RTA` are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarStyle {
+    /// Solid bars made of block characters.
+    #[default]
+    Filled,
+    /// Hollow bars drawn as a top cap and side edges, leaving whatever is behind them visible.
+    Outline,
+}
+
+/// How the fractional top of a bar is rounded to the eighth blocks available for drawing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartialRounding {
+    /// Round down, so a bar never shows more than its value.
+    #[default]
+    Floor,
+    /// Round to the nearest eighth.
+    Nearest,
+    /// Round up, so any value shows at least up to the next eighth.
+    Ceil,
+    /// Round each column of a bar with a different offset, so wide bars show levels between
+    /// eighths.
+    Dithered,
+}
+
+/// The stops of [`RTA::rainbow`].
+pub(crate) const RAINBOW: [Color; 6] = [
+    Color::Rgb(255, 0, 0),
+    Color::Rgb(255, 140, 0),
+    Color::Rgb(255, 230, 0),
+    Color::Rgb(0, 200, 0),
+    Color::Rgb(0, 110, 255),
+    Color::Rgb(150, 0, 255),
+];
+
+/// The color at `position` between 0 and 1 of a gradient through `stops`, which must not be empty.
+pub(crate) fn gradient_color(stops: &[Color], position: f32) -> Color {
+    let scaled = position.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let i = (scaled as usize).min(stops.len() - 1);
+    match stops.get(i + 1) {
+        Some(&next) => blend_color(stops[i], next, scaled - i as f32),
+        None => stops[i],
+    }
+}
+
+/// Which inputs an `RTA` comparing two inputs shows, set with [`RTA::compare`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Comparison {
+    /// Only the bands the `RTA` was created with.
+    #[default]
+    A,
+    /// Only the bands of the second input.
+    B,
+    /// Input A as bars with input B as outlined bars on top.
+    Both,
+}
+
+/// How an `RTA` uses color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorMode {
+    /// Use the configured colors.
+    #[default]
+    Color,
+    /// Use bright colors on a black background, for low vision or washed-out displays.
+    HighContrast,
+    /// Use no colors at all, for colorblind users and monochrome terminals. Stacked channels and
+    /// the black keys of the piano keyboard are told apart by their fill pattern instead.
+    Monochrome,
+}
+
+/// A frequency response drawn as a curve on top of the RTA bars.
+#[derive(Debug, Clone)]
+pub(crate) struct ReferenceCurve<'a> {
+    /// The points of the curve, sorted by frequency.
+    pub(crate) points: &'a [FrequencyPoint],
+    /// The color of the curve.
+    pub(crate) color: Color,
+}
+
+/// The side-chain input of a dynamic processor, shown with the bands of its signal.
+#[derive(Debug, Clone)]
+pub(crate) struct Sidechain {
+    /// The bands of the side-chain input.
+    pub(crate) bands: Vec<Band>,
+    /// The level in dB at or above which a side-chain band triggers the processor.
+    pub(crate) threshold_db: f32,
+}
+
+/// Per-band levels computed outside the meter, drawn as markers on top of the RTA bars.
+#[derive(Debug, Clone)]
+pub(crate) struct BandMarkers<'a> {
+    /// The level in dB of each band, or NaN for no marker.
+    pub(crate) values: &'a [f32],
+    /// The color of the markers.
+    pub(crate) color: Color,
+}
+
+/// A function returning the style patched onto a band, given its index and the band.
+type StyleFn<'a> = dyn Fn(usize, &Band) -> Style + 'a;
+
+/// A [`StyleFn`] that can be stored in a widget.
+#[derive(Clone)]
+pub(crate) struct BandStyleFn<'a>(pub(crate) Rc<StyleFn<'a>>);
+
+impl fmt::Debug for BandStyleFn<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BandStyleFn")
+    }
+}
+
+/// A function returning the normalized value drawn for a band.
+type ValueFn<'a> = dyn Fn(&Band) -> f32 + 'a;
+
+/// A [`ValueFn`] that can be stored in a widget.
+#[derive(Clone)]
+pub(crate) struct ValueMap<'a>(pub(crate) Rc<ValueFn<'a>>);
+
+impl fmt::Debug for ValueMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ValueMap")
+    }
 }
 
 /// A struct representing a single frequency band in the RTA meter.
@@ -23,6 +440,18 @@ pub struct Band {
     pub color: Color,
     /// Frequency band label, if any. Used for rendering frequency labels.
     pub frequency: Option<u16>,
+    /// The standard deviation of the band in dB, if known. Used for rendering error bars.
+    pub deviation_db: Option<f32>,
+    /// Whether the last value given to [`Band::set_ratio`] or [`Band::set_db`] was NaN, e.g. from a
+    /// bad DSP sample. The band then shows as empty.
+    pub invalid: bool,
+    /// Whether the last magnitude given to [`Band::set_magnitude`] was at or below the magnitude
+    /// floor, i.e. the band is silent rather than just quiet. Silent bands show as empty and are
+    /// never the peak band.
+    pub silent: bool,
+    /// The linear energy each channel contributes to the band, if known. Used for rendering
+    /// stacked channels.
+    pub channel_energies: Vec<f32>,
 }
 
 impl Band {
@@ -31,17 +460,54 @@ impl Band {
             value,
             color: Color::Yellow,
             frequency: Some(frequency),
+            deviation_db: None,
+            invalid: false,
+            silent: false,
+            channel_energies: Vec::new(),
         }
     }
 
+    /// The default magnitude floor for [`Band::set_magnitude`], -200 dB.
+    pub const DEFAULT_MAGNITUDE_FLOOR: f32 = 1e-10;
+
+    /// Sets the value of the band from a linear magnitude, e.g. of an FFT bin, where 1.0 is 0 dB.
+    ///
+    /// Magnitudes at or below `floor` mark the band silent and empty it, instead of taking the
+    /// logarithm of 0. Use [`Band::DEFAULT_MAGNITUDE_FLOOR`] unless the analysis has a different
+    /// noise floor. NaN empties the band and marks it invalid.
+    pub fn set_magnitude(&mut self, magnitude: f32, floor: f32, min_db: f32) {
+        let magnitude = magnitude.abs();
+        if magnitude <= floor {
+            self.invalid = false;
+            self.value = 0.0;
+            self.silent = true;
+            return;
+        }
+        self.set_db(20.0 * magnitude.log10(), min_db);
+    }
+
     /// Sets the value of the band as a ratio between 0.0 and 1.0.
+    ///
+    /// NaN empties the band and marks it invalid, infinite values are clamped to the scale.
     pub fn set_ratio(&mut self, value: f32) {
-        self.value = value;
+        self.silent = false;
+        self.invalid = value.is_nan();
+        self.value = if self.invalid {
+            0.0
+        } else if value.is_infinite() {
+            value.clamp(0.0, 1.0)
+        } else {
+            value
+        };
     }
 
     /// Set the value of the band in decibels.
+    ///
+    /// NaN empties the band and marks it invalid, infinite values are clamped to the scale.
     pub fn set_db(&mut self, db: f32, min_db: f32) {
-        if db <= min_db {
+        self.silent = false;
+        self.invalid = db.is_nan();
+        if self.invalid || db <= min_db {
             self.value = 0.0;
             return;
         }
@@ -63,6 +529,15 @@ impl Band {
             10_f32.powf(self.value * (0.0 - min_db_ratio.log10()) + min_db_ratio.log10());
         20.0 * db_ratio.log10()
     }
+
+    /// The value of the band as drawn, clamped to 0.0..=1.0 with NaN treated as empty.
+    pub(crate) fn display_value(&self) -> f32 {
+        if self.value.is_nan() {
+            0.0
+        } else {
+            self.value.clamp(0.0, 1.0)
+        }
+    }
 }
 
 impl<'a> RTA<'a> {
@@ -70,20 +545,158 @@ impl<'a> RTA<'a> {
     pub fn new(bands: Vec<Band>, min_db: f32) -> Self {
         RTA {
             block: None,
+            padding: Padding::ZERO,
             bands,
             show_peak_labels: true,
+            highlight_peak_band: false,
+            peak_exclusion: None,
+            show_band_maxima: false,
+            band_maxima: Vec::new(),
+            comparison: None,
+            theme: Theme::terminal_default(),
+            peak_dots: 0,
+            peak_history: Vec::new(),
+            ambient_timeout: None,
+            sidechain: None,
+            held_peak_band: None,
+            peak_labels_in_title: false,
             min_db,
+            reference_curves: Vec::new(),
+            masking_curve: None,
+            markers: Vec::new(),
+            error_bars: None,
+            alert_threshold_db: None,
+            alert_style: Style::new().fg(Color::Red),
+            alert_hold: Duration::from_secs(1),
+            alert_blink: AlertBlink::default(),
+            idle_threshold_db: None,
+            idle_timeout: Duration::from_secs(5),
+            idle_style: Style::new().fg(Color::DarkGray),
+            flag_invalid_bands: false,
+            reference_level_db: None,
+            display_scale: DisplayScale::default(),
+            frequency_axis: FrequencyAxis::default(),
+            axis_style: AxisStyle::default(),
+            piano_keyboard: false,
+            bar_style: BarStyle::default(),
+            channel_colors: None,
+            partial_rounding: PartialRounding::default(),
+            min_visible: false,
+            min_visible_gate_db: None,
+            color_mode: ColorMode::default(),
+            number_format: NumberFormat::default(),
+            footer: None,
+            top_ruler: TopRuler::default(),
+            session_time: None,
+            overlays: Vec::new(),
+            anti_aliased_curves: false,
+            emphasized_frequencies: None,
+            band_style_fn: None,
+            value_map: None,
         }
     }
 
+    /// The bands of the meter.
+    pub fn bands(&self) -> &[Band] {
+        &self.bands
+    }
+
+    /// The bands of the meter, for changing them after construction.
+    pub fn bands_mut(&mut self) -> &mut [Band] {
+        &mut self.bands
+    }
+
     /// Highlights the band with the maximum value by changing its color to red.
+    ///
+    /// When rendered as a stateful widget, the highlight can be held on a band with
+    /// [`crate::RTAState::hold_peak_band`] so it doesn't flicker between bands of nearly equal
+    /// level.
     pub fn highlight_peak_band(mut self) -> Self {
-        if let Some((max_index, _)) = self.bands.iter().enumerate().max_by(|(_, a), (_, b)| {
-            a.value
-                .partial_cmp(&b.value)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        }) {
-            self.bands[max_index].color = Color::Red;
+        self.highlight_peak_band = true;
+        self
+    }
+
+    /// Ignores bands with a frequency in `frequencies` when finding the peak band for the
+    /// highlight and the peak readout, e.g. `0..40` so room rumble doesn't always win.
+    ///
+    /// Bands without a frequency are never ignored.
+    pub fn peak_exclusion(mut self, frequencies: Range<u16>) -> Self {
+        self.peak_exclusion = Some(frequencies);
+        self
+    }
+
+    /// Sets whether to label each band with the highest level in dB it reached, e.g. to document
+    /// worst-case levels per band. Labels are drawn above the maximum of bands wide enough for
+    /// them.
+    ///
+    /// The maxima are tracked in [`crate::RTAState`] from the first stateful render, so the widget
+    /// must be rendered as a stateful widget. Restart them with
+    /// [`crate::RTAState::reset_band_maxima`].
+    pub fn show_band_maxima(mut self, show: bool) -> Self {
+        self.show_band_maxima = show;
+        self
+    }
+
+    /// Compares the bands with `bands_b` from a second input, e.g. another microphone or
+    /// processing chain, showing the inputs selected by `comparison`.
+    ///
+    /// Switching between [`Comparison::A`] and [`Comparison::B`] takes effect on the next frame,
+    /// with peak labels, alerts and highlights following the shown input. With
+    /// [`Comparison::Both`], input B is drawn as outlined bars over input A.
+    pub fn compare(mut self, bands_b: Vec<Band>, comparison: Comparison) -> Self {
+        self.comparison = Some((bands_b, comparison));
+        self
+    }
+
+    /// Shows the spectrum of a side-chain or key input, e.g. of a dynamic EQ, as outlined bars over
+    /// the signal's bars, and highlights the trigger band: the loudest side-chain band at or above
+    /// `threshold_db`.
+    ///
+    /// The side-chain bands must match the signal's bands.
+    pub fn sidechain(mut self, bands: Vec<Band>, threshold_db: f32) -> Self {
+        self.sidechain = Some(Sidechain {
+            bands,
+            threshold_db,
+        });
+        self
+    }
+
+    /// The index of the side-chain band that triggers the processor, the loudest at or above the
+    /// threshold, if there is a side-chain and any band reaches it.
+    pub fn sidechain_trigger(&self) -> Option<usize> {
+        let sidechain = self.sidechain.as_ref()?;
+        sidechain
+            .bands
+            .iter()
+            .enumerate()
+            .filter(|(_, band)| !band.value.is_nan())
+            .map(|(index, band)| (index, band.get_db(self.min_db)))
+            .filter(|&(_, db)| db >= sidechain.threshold_db)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+
+    /// Colors the bands by their position on the frequency axis, from red at the lowest band
+    /// through the rainbow to violet at the highest.
+    ///
+    /// This recolors the current bands.
+    pub fn rainbow(self) -> Self {
+        self.frequency_gradient(RAINBOW)
+    }
+
+    /// Colors the bands by their position on the frequency axis, spreading the `stops` evenly from
+    /// the lowest to the highest band.
+    ///
+    /// RGB stops are blended smoothly, other colors switch at the midpoint between stops. This
+    /// recolors the current bands and does nothing without stops.
+    pub fn frequency_gradient(mut self, stops: impl Into<Vec<Color>>) -> Self {
+        let stops = stops.into();
+        if stops.is_empty() {
+            return self;
+        }
+        let last = self.bands.len().saturating_sub(1).max(1) as f32;
+        for (i, band) in self.bands.iter_mut().enumerate() {
+            band.color = gradient_color(&stops, i as f32 / last);
         }
         self
     }
@@ -94,6 +707,382 @@ impl<'a> RTA<'a> {
         self
     }
 
+    /// Shows the peak labels on one line, right-aligned in the title line of the block, instead of
+    /// on two rows above the meter. This leaves more room for the bars in short areas.
+    ///
+    /// Without a block with a top border, the peak labels stay above the meter.
+    pub fn peak_labels_in_title(mut self, in_title: bool) -> Self {
+        self.peak_labels_in_title = in_title;
+        self
+    }
+
+    /// Draws a frequency response, e.g. an imported measurement, as a curve on top of the bars.
+    ///
+    /// The magnitudes are read on the same dB scale as the bands and resampled to the bands with
+    /// [`crate::resample_curve`]. `points` must be sorted by frequency, as returned by
+    /// [`crate::parse_frequency_response`].
+    pub fn reference_curve(mut self, points: &'a [FrequencyPoint], color: Color) -> Self {
+        self.reference_curves.push(ReferenceCurve { points, color });
+        self
+    }
+
+    /// Draws the visible traces of `store` as curves on top of the bars.
+    pub fn traces(mut self, store: &'a TraceStore) -> Self {
+        self.reference_curves
+            .extend(store.visible().map(|trace| ReferenceCurve {
+                points: &trace.points,
+                color: trace.color,
+            }));
+        self
+    }
+
+    /// Sets whether reference curves and traces are smoothed by shading the empty cells next to
+    /// them with `░▒▓`, depending on how close the curve passes. This looks smoother on large
+    /// terminals, but fills more cells.
+    pub fn anti_aliased_curves(mut self, anti_aliased: bool) -> Self {
+        self.anti_aliased_curves = anti_aliased;
+        self
+    }
+
+    /// Draws the estimated masking threshold of the bands as a curve, see
+    /// [`crate::masking_threshold`]. Content below the curve is likely masked by louder content
+    /// nearby and inaudible.
+    pub fn masking_curve(mut self, color: Color) -> Self {
+        self.masking_curve = Some(color);
+        self
+    }
+
+    /// Draws per-band levels computed outside the meter as markers on top of the bars, e.g. the
+    /// level of each band before a multiband compressor, so the gap down to the bar shows the gain
+    /// reduction.
+    ///
+    /// `values` holds one level in dB per band, on the same scale as the bands. NaN and levels
+    /// below the meter draw no marker. Markers can be added several times for several datasets.
+    pub fn markers(mut self, values: &'a [f32], color: Color) -> Self {
+        self.markers.push(BandMarkers { values, color });
+        self
+    }
+
+    /// Draws the deviation of bands that have one, see [`crate::BandAverager`].
+    pub fn error_bars(mut self, style: ErrorBarStyle) -> Self {
+        self.error_bars = Some(style);
+        self
+    }
+
+    /// Triggers an alert when any band reaches `threshold_db`, e.g. 0 dB to flag clipping.
+    ///
+    /// While the alert is active, the surrounding block is restyled with the alert style. Alerts
+    /// are tracked in [`crate::RTAState`], so the widget must be rendered as a stateful widget.
+    pub fn alert_threshold_db(mut self, threshold_db: f32) -> Self {
+        self.alert_threshold_db = Some(threshold_db);
+        self
+    }
+
+    /// Sets the style patched onto the block's borders while an alert is active. Defaults to red.
+    pub fn alert_style(mut self, style: impl Into<Style>) -> Self {
+        self.alert_style = style.into();
+        self
+    }
+
+    /// Sets how long an alert stays active after it was triggered. Defaults to one second.
+    pub fn alert_hold(mut self, hold: Duration) -> Self {
+        self.alert_hold = hold;
+        self
+    }
+
+    /// Sets how the alert style blinks while an alert is active. Defaults to [`AlertBlink::Off`].
+    pub fn alert_blink(mut self, blink: AlertBlink) -> Self {
+        self.alert_blink = blink;
+        self
+    }
+
+    /// Switches to a dimmed idle style with a "No signal" note once all bands have stayed below
+    /// `threshold_db` for the idle timeout. The widget wakes up as soon as any band rises above it.
+    ///
+    /// Silence is tracked in [`crate::RTAState`], so the widget must be rendered as a stateful
+    /// widget.
+    pub fn idle_threshold_db(mut self, threshold_db: f32) -> Self {
+        self.idle_threshold_db = Some(threshold_db);
+        self
+    }
+
+    /// Sets how long the signal must be absent before the widget goes idle. Defaults to five
+    /// seconds.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Sets the style applied to the widget while idle. Defaults to dark gray.
+    pub fn idle_style(mut self, style: impl Into<Style>) -> Self {
+        self.idle_style = style.into();
+        self
+    }
+
+    /// Marks bands that were given NaN values with a red `!` at the bottom of their bar.
+    pub fn flag_invalid_bands(mut self, flag: bool) -> Self {
+        self.flag_invalid_bands = flag;
+        self
+    }
+
+    /// Draws a labelled horizontal line across the bars at `db`, to compare band levels against it.
+    ///
+    /// When rendered as a stateful widget, a level selected with [`crate::RTAState::select_at`]
+    /// takes precedence.
+    pub fn reference_level_db(mut self, db: f32) -> Self {
+        self.reference_level_db = Some(db);
+        self
+    }
+
+    /// Sets how levels are mapped to the height of the bars. Defaults to [`DisplayScale::Decibel`].
+    ///
+    /// With [`DisplayScale::Percent`] the axis is labelled from 0% to 100% of full scale
+    /// amplitude. Bands are still set in dB or as ratios of the dB scale.
+    pub fn display_scale(mut self, scale: DisplayScale) -> Self {
+        self.display_scale = scale;
+        self
+    }
+
+    /// Sets how the frequency axis is labelled. Defaults to [`FrequencyAxis::Hertz`].
+    pub fn frequency_axis(mut self, axis: FrequencyAxis) -> Self {
+        self.frequency_axis = axis;
+        self
+    }
+
+    /// Sets which sides of the bars have axis lines and how they are drawn. Defaults to
+    /// [`AxisStyle::left_bottom`].
+    pub fn axis_style(mut self, style: AxisStyle) -> Self {
+        self.axis_style = style;
+        self
+    }
+
+    /// Draws a one-row piano keyboard below the frequency axis, aligned with the bands, so peaks
+    /// can be read as musical pitches.
+    ///
+    /// Every C is marked with its octave number. All bands need a frequency for the keyboard to
+    /// be drawn.
+    pub fn piano_keyboard(mut self, show: bool) -> Self {
+        self.piano_keyboard = show;
+        self
+    }
+
+    /// Sets how the bars are drawn. Defaults to [`BarStyle::Filled`].
+    ///
+    /// Outlined bars only draw their edges, so when rendering several `RTA` widgets on top of each
+    /// other, e.g. one per channel, the bars rendered first stay visible.
+    pub fn bar_style(mut self, style: BarStyle) -> Self {
+        self.bar_style = style;
+        self
+    }
+
+    /// Partitions each bar vertically by the share of [`Band::channel_energies`] each channel
+    /// contributes, with the first channel at the bottom.
+    ///
+    /// Channel `i` is drawn in `colors[i]`, cycling through the colors if there are more channels.
+    /// Bands without channel energies are drawn as usual.
+    pub fn stacked_channels(mut self, colors: impl Into<Vec<Color>>) -> Self {
+        let colors = colors.into();
+        self.channel_colors = (!colors.is_empty()).then_some(colors);
+        self
+    }
+
+    /// Sets how the fractional top of a bar is rounded to eighth blocks. Defaults to
+    /// [`PartialRounding::Floor`], which under-reports small values.
+    pub fn partial_rounding(mut self, rounding: PartialRounding) -> Self {
+        self.partial_rounding = rounding;
+        self
+    }
+
+    /// Sets whether active bands show at least an eighth block, so low-level activity doesn't
+    /// disappear at the bottom of the scale.
+    ///
+    /// Without a gate set with [`RTA::min_visible_gate_db`], any band with a non-zero value is
+    /// active.
+    pub fn min_visible(mut self, min_visible: bool) -> Self {
+        self.min_visible = min_visible;
+        self
+    }
+
+    /// Sets the level in dB a band must exceed to be shown by [`RTA::min_visible`], so noise
+    /// below the gate still renders as empty.
+    pub fn min_visible_gate_db(mut self, gate_db: f32) -> Self {
+        self.min_visible_gate_db = Some(gate_db);
+        self
+    }
+
+    /// Sets how the meter uses color. Defaults to [`ColorMode::Color`].
+    ///
+    /// The mode applies to everything drawn in the widget's area, including the block.
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self
+    }
+
+    /// Draws the last `count` peaks of each band as dots above its bar, the most recent brightest,
+    /// to show recent dynamics. A peak is a level at which the band stopped rising and started to
+    /// fall. Zero disables the dots.
+    ///
+    /// Peaks are tracked in [`crate::RTAState`], so the widget must be rendered as a stateful
+    /// widget.
+    pub fn peak_dots(mut self, count: usize) -> Self {
+        self.peak_dots = count;
+        self
+    }
+
+    /// Switches to an ambient mode for always-on displays once there was no user activity for
+    /// `timeout`. It shows only smoothed bars in rainbow colors across the whole area, without
+    /// the block, axes and readouts.
+    ///
+    /// Activity is tracked in [`crate::RTAState`], so the widget must be rendered as a stateful
+    /// widget, and the app reports keypresses with [`crate::RTAState::record_activity`] to return
+    /// to the full meter.
+    pub fn ambient_after(mut self, timeout: Duration) -> Self {
+        self.ambient_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the colors around the bands. When rendered as a stateful widget, a theme set with
+    /// [`crate::RTAState::set_theme`] takes precedence.
+    ///
+    /// With the `detect-background` feature, the default is the detected theme on light terminals.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Sets how the numeric labels are formatted, e.g. to use a decimal comma or localized unit
+    /// names.
+    pub fn number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    /// Shows `footer` on a row of its own under the frequency axis, aligned with the bars, e.g. for
+    /// status text of the app.
+    pub fn footer(mut self, footer: impl Into<Line<'a>>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// Shows a row above the bars with a clock or the session time, right-aligned with the bars.
+    /// Defaults to [`TopRuler::None`], which leaves out the row.
+    pub fn top_ruler(mut self, ruler: TopRuler) -> Self {
+        self.top_ruler = ruler;
+        self
+    }
+
+    /// Adds an [`Overlay`] drawn on top of the bars, e.g. a curve or markers of the app.
+    pub fn overlay(mut self, overlay: &'a dyn Overlay) -> Self {
+        self.overlays.push(OverlayRef(overlay));
+        self
+    }
+
+    /// Styles each band with the style returned by `style_fn` for its index and the band, e.g. to
+    /// stripe every other band or dim bands outside a range of interest.
+    ///
+    /// The style is patched onto the whole column of the band after the bar is drawn, so unlike
+    /// setting [`Band::color`] it can also set the background and modifiers.
+    pub fn band_style_fn(mut self, style_fn: impl Fn(usize, &Band) -> Style + 'a) -> Self {
+        self.band_style_fn = Some(BandStyleFn(Rc::new(style_fn)));
+        self
+    }
+
+    /// Draws each band with the normalized value returned by `value_map` instead of
+    /// [`Band::value`], e.g. to apply a display-only gain or companding without changing the
+    /// measured values.
+    ///
+    /// Only the height of the bars is mapped. Peak labels, error bars, alerts and overlays use the
+    /// measured values.
+    pub fn value_map(mut self, value_map: impl Fn(&Band) -> f32 + 'a) -> Self {
+        self.value_map = Some(ValueMap(Rc::new(value_map)));
+        self
+    }
+
+    /// Draws the bands with a frequency in `frequencies` bold and dims all others.
+    ///
+    /// The style of [`RTA::band_style_fn`], if set, is patched on top.
+    pub fn emphasize_frequencies(mut self, frequencies: RangeInclusive<u16>) -> Self {
+        self.emphasized_frequencies = Some(frequencies);
+        self
+    }
+
+    /// Emphasizes the [`CODEC_ARTIFACT_FREQUENCIES`], where lossy codecs cut off or smear the
+    /// highest frequencies, with [`RTA::emphasize_frequencies`].
+    ///
+    /// Pair it with [`crate::SpectralFlux`] to also flag the transients where pre-echo appears.
+    pub fn codec_artifacts(self) -> Self {
+        self.emphasize_frequencies(CODEC_ARTIFACT_FREQUENCIES)
+    }
+
+    /// The style of a band emphasized or dimmed with [`RTA::emphasize_frequencies`], if set.
+    pub(crate) fn emphasis_style(&self, band: &Band) -> Option<Style> {
+        let frequencies = self.emphasized_frequencies.as_ref()?;
+        let modifier = match band.frequency {
+            Some(frequency) if frequencies.contains(&frequency) => Modifier::BOLD,
+            _ => Modifier::DIM,
+        };
+        Some(Style::new().add_modifier(modifier))
+    }
+
+    /// The index of the band whose frequency range contains `frequency` in Hz, if any.
+    ///
+    /// Bands extend halfway to their neighbors on a logarithmic scale, and the outermost bands
+    /// just as far outwards. A single band covers all frequencies. The bands must be sorted by
+    /// frequency, and bands without one are never found.
+    pub fn band_index_for_freq(&self, frequency: f32) -> Option<usize> {
+        let index = self.nearest_band(frequency)?;
+        let center = self.band_frequency(index)?;
+        let below = index.checked_sub(1).and_then(|i| self.band_frequency(i));
+        let above = self.band_frequency(index + 1);
+        // Only frequencies beyond the outer half of the outermost bands can be too far away. The
+        // width of that half is mirrored from the inner half.
+        let neighbor = if frequency < center && below.is_none() {
+            above
+        } else if frequency > center && above.is_none() {
+            below
+        } else {
+            return Some(index);
+        };
+        let Some(neighbor) = neighbor else {
+            // A single band covers all frequencies.
+            return Some(index);
+        };
+        let half_width = (neighbor / center).log2().abs() / 2.0;
+        ((frequency / center).log2().abs() <= half_width).then_some(index)
+    }
+
+    /// The index of the band with the center frequency nearest to `frequency` in Hz on a
+    /// logarithmic scale, or `None` if no band has a frequency.
+    ///
+    /// The bands must be sorted by frequency, and bands without one are never found.
+    pub fn nearest_band(&self, frequency: f32) -> Option<usize> {
+        if frequency.is_nan() || frequency <= 0.0 {
+            return None;
+        }
+        let above = self.bands.partition_point(|band| {
+            band.frequency
+                .is_some_and(|center| (center as f32) < frequency)
+        });
+        let distance = |index: usize| {
+            self.band_frequency(index)
+                .map_or(f32::INFINITY, |center| (frequency / center).log2().abs())
+        };
+        [above.checked_sub(1), Some(above)]
+            .into_iter()
+            .flatten()
+            .filter(|&index| self.band_frequency(index).is_some())
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+    }
+
+    /// The center frequency of the band at `index` in Hz, if it exists and has a positive one.
+    fn band_frequency(&self, index: usize) -> Option<f32> {
+        self.bands
+            .get(index)?
+            .frequency
+            .filter(|&frequency| frequency > 0)
+            .map(f32::from)
+    }
+
     /// Surrounds the `RTA` widget with a [`Block`].
     ///
     /// The meter is rendered in the inner portion of the block once space for borders and padding
@@ -103,4 +1092,102 @@ impl<'a> RTA<'a> {
         self.block = Some(block);
         self
     }
+
+    /// Adds empty space around the meter, inside the block but outside the axes. Defaults to no
+    /// padding.
+    ///
+    /// Use this instead of wrapping the widget in another block for spacing, so the meter stays
+    /// aligned with adjacent widgets.
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rta(frequencies: &[Option<u16>]) -> RTA<'static> {
+        let bands = frequencies
+            .iter()
+            .map(|&frequency| Band {
+                frequency,
+                ..Band::new(0.5, 0)
+            })
+            .collect();
+        RTA::new(bands, -60.0)
+    }
+
+    #[test]
+    fn finds_band_at_and_between_centers() {
+        let rta = rta(&[Some(100), Some(200), Some(400)]);
+        assert_eq!(rta.band_index_for_freq(100.0), Some(0));
+        assert_eq!(rta.band_index_for_freq(200.0), Some(1));
+        assert_eq!(rta.band_index_for_freq(400.0), Some(2));
+        // The edge between 100 and 200 Hz lies at 141 Hz.
+        assert_eq!(rta.band_index_for_freq(140.0), Some(0));
+        assert_eq!(rta.band_index_for_freq(143.0), Some(1));
+        assert_eq!(rta.nearest_band(140.0), Some(0));
+        assert_eq!(rta.nearest_band(143.0), Some(1));
+    }
+
+    #[test]
+    fn outer_bands_extend_half_a_band_outwards() {
+        let rta = rta(&[Some(100), Some(200), Some(400)]);
+        // The outer edges lie at 71 and 566 Hz.
+        assert_eq!(rta.band_index_for_freq(72.0), Some(0));
+        assert_eq!(rta.band_index_for_freq(70.0), None);
+        assert_eq!(rta.band_index_for_freq(560.0), Some(2));
+        assert_eq!(rta.band_index_for_freq(570.0), None);
+        assert_eq!(rta.nearest_band(10.0), Some(0));
+        assert_eq!(rta.nearest_band(20000.0), Some(2));
+    }
+
+    #[test]
+    fn single_band_covers_all_frequencies() {
+        let rta = rta(&[Some(1000)]);
+        assert_eq!(rta.band_index_for_freq(1.0), Some(0));
+        assert_eq!(rta.band_index_for_freq(1000.0), Some(0));
+        assert_eq!(rta.band_index_for_freq(100000.0), Some(0));
+    }
+
+    #[test]
+    fn invalid_frequency_is_not_found() {
+        let rta = rta(&[Some(100), Some(200)]);
+        for frequency in [f32::NAN, 0.0, -100.0] {
+            assert_eq!(rta.band_index_for_freq(frequency), None);
+            assert_eq!(rta.nearest_band(frequency), None);
+        }
+    }
+
+    #[test]
+    fn codec_artifacts_compose_with_band_style() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+        let area = Rect::new(0, 0, 40, 12);
+        let mut buf = Buffer::empty(area);
+        let mut state = crate::RTAState::new();
+        rta(&[Some(1000), Some(16000)])
+            .codec_artifacts()
+            .band_style_fn(|_, _| Style::new().add_modifier(Modifier::ITALIC))
+            .render(area, &mut buf, &mut state);
+        let areas = state.geometry.as_ref().unwrap().band_areas();
+        let modifier = |index: usize| buf[(areas[index].x, areas[index].bottom() - 1)].modifier;
+        assert_eq!(modifier(0), Modifier::DIM | Modifier::ITALIC);
+        assert_eq!(modifier(1), Modifier::BOLD | Modifier::ITALIC);
+    }
+
+    #[test]
+    fn bands_without_frequency_are_never_found() {
+        assert_eq!(rta(&[None, None]).band_index_for_freq(1000.0), None);
+        assert_eq!(rta(&[None, None]).nearest_band(1000.0), None);
+        assert_eq!(rta(&[]).band_index_for_freq(1000.0), None);
+
+        // The last band with a frequency is the outermost one.
+        let rta = rta(&[Some(100), Some(200), None]);
+        assert_eq!(rta.nearest_band(1000.0), Some(1));
+        assert_eq!(rta.band_index_for_freq(250.0), Some(1));
+        assert_eq!(rta.band_index_for_freq(300.0), None);
+    }
 }
@@ -0,0 +1,165 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::ConfigError,
+    spl::{FrequencyWeighting, TimeWeighting},
+    theme::Theme,
+};
+
+/// Parameters that users typically adjust while the analyzer is running.
+///
+/// ```toml
+/// min_db = -70.0
+/// show_peak_labels = false
+/// frequency_weighting = "C"
+/// time_weighting = "Slow"
+/// calibration_db = 114.0
+/// high_pass_enabled = true
+/// averaging = true
+///
+/// [theme]
+/// background = "#101010"
+/// foreground = "#e6e6e6"
+/// ```
+///
+/// The zoom of the RTA is its `min_db`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// The lowest value of the dB scale of the RTA.
+    pub min_db: f32,
+    /// Whether to show the peak labels at the top of the RTA.
+    pub show_peak_labels: bool,
+    /// The frequency weighting of the SPL meter.
+    pub frequency_weighting: FrequencyWeighting,
+    /// The time weighting of the SPL meter.
+    pub time_weighting: TimeWeighting,
+    /// The level in dB SPL of a full scale sine wave.
+    pub calibration_db: f32,
+    /// Whether the DC blocking high-pass filter is enabled.
+    pub high_pass_enabled: bool,
+    /// Whether the bands are averaged over time with a [`crate::BandAverager`].
+    pub averaging: bool,
+    /// The theme of the RTA, or `None` for the terminal's colors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            min_db: -90.0,
+            show_peak_labels: true,
+            frequency_weighting: FrequencyWeighting::default(),
+            time_weighting: TimeWeighting::default(),
+            calibration_db: crate::spl::SplMeterState::DEFAULT_CALIBRATION_DB,
+            high_pass_enabled: true,
+            averaging: false,
+            theme: None,
+        }
+    }
+}
+
+/// [`Settings`] that are saved to a TOML file whenever they change.
+///
+/// ```no_run
+/// # use rataudio_rta::PersistentSettings;
+/// let mut settings = PersistentSettings::load("settings.toml")?;
+/// settings.update(|s| s.min_db = -70.0)?;
+/// # Ok::<(), rataudio_rta::ConfigError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PersistentSettings {
+    path: PathBuf,
+    settings: Settings,
+}
+
+impl PersistentSettings {
+    /// Restores the settings saved at `path`, or uses the defaults if the file doesn't exist yet.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let settings = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(ConfigError::Parse)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Settings::default(),
+            Err(err) => return Err(ConfigError::Io(err)),
+        };
+        Ok(PersistentSettings { path, settings })
+    }
+
+    /// The current settings.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The file the settings are saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Changes the settings with `f` and saves them if anything changed.
+    ///
+    /// Returns whether the settings changed.
+    pub fn update(&mut self, f: impl FnOnce(&mut Settings)) -> Result<bool, ConfigError> {
+        let previous = self.settings.clone();
+        f(&mut self.settings);
+        if self.settings == previous {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Saves the current settings.
+    ///
+    /// The file is replaced atomically so an interrupted save doesn't lose the previous settings.
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let content = toml::to_string(&self.settings).map_err(ConfigError::Serialize)?;
+        let temp_path = self.path.with_extension("toml.tmp");
+        fs::write(&temp_path, content).map_err(ConfigError::Io)?;
+        fs::rename(&temp_path, &self.path).map_err(ConfigError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let settings = Settings {
+            min_db: -70.0,
+            averaging: true,
+            theme: Some(Theme {
+                bars: Some(Color::Cyan),
+                ..Theme::dark()
+            }),
+            ..Settings::default()
+        };
+        let content = toml::to_string(&settings).unwrap();
+        assert_eq!(toml::from_str::<Settings>(&content).unwrap(), settings);
+    }
+
+    #[test]
+    fn parses_theme_colors() {
+        let settings: Settings = toml::from_str(
+            "averaging = true\n[theme]\nbackground = \"#101010\"\nforeground = \"white\"\n",
+        )
+        .unwrap();
+        assert!(settings.averaging);
+        assert_eq!(
+            settings.theme,
+            Some(Theme {
+                background: Color::Rgb(16, 16, 16),
+                foreground: Color::White,
+                bars: None,
+            })
+        );
+    }
+}
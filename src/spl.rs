@@ -0,0 +1,443 @@
+use std::f64::consts::PI;
+
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Paragraph, StatefulWidget},
+};
+
+use crate::{big_number::BigNumber, filter::Biquad, rendering::HORIZONTAL_PARTIAL_BLOCKS};
+
+/// Pole frequencies of the A and C weighting curves in Hz, as defined in IEC 61672-1.
+const WEIGHTING_POLES_HZ: [f64; 4] = [20.598997, 107.65265, 737.86223, 12194.217];
+
+/// The frequency weighting applied before measuring the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrequencyWeighting {
+    /// A weighting, approximating the sensitivity of hearing at low levels.
+    #[default]
+    A,
+    /// C weighting, flatter than A and used for peak and high-level measurements.
+    C,
+    /// No weighting.
+    Z,
+}
+
+/// The time weighting (ballistics) of the level measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeWeighting {
+    /// 125 ms time constant.
+    #[default]
+    Fast,
+    /// 1 s time constant.
+    Slow,
+    /// 35 ms rise and 1.5 s decay time constant.
+    Impulse,
+}
+
+impl FrequencyWeighting {
+    /// The letter used in level units such as dB(A).
+    pub fn label(&self) -> &'static str {
+        match self {
+            FrequencyWeighting::A => "A",
+            FrequencyWeighting::C => "C",
+            FrequencyWeighting::Z => "Z",
+        }
+    }
+
+    /// The filter sections implementing the weighting, normalized to 0 dB at 1 kHz.
+    ///
+    /// The poles are prewarped for the bilinear transform, so the 12.2 kHz pole stays in place
+    /// and the response above 8 kHz stays within the IEC 61672 class 1 tolerances.
+    fn filters(&self, sample_rate: f32) -> Vec<Biquad> {
+        let fs = sample_rate as f64;
+        let [w1, w2, w3, w4] = WEIGHTING_POLES_HZ.map(|f| 2.0 * fs * (PI * f / fs).tan());
+        // s² / (s + w1)² and w4² / (s + w4)² are shared by A and C weighting.
+        let mut filters = match self {
+            FrequencyWeighting::Z => return Vec::new(),
+            FrequencyWeighting::A | FrequencyWeighting::C => vec![
+                Biquad::bilinear([1.0, 0.0, 0.0], [1.0, 2.0 * w1, w1 * w1], sample_rate),
+                Biquad::bilinear([0.0, 0.0, w4 * w4], [1.0, 2.0 * w4, w4 * w4], sample_rate),
+            ],
+        };
+        if *self == FrequencyWeighting::A {
+            filters.push(Biquad::bilinear(
+                [1.0, 0.0, 0.0],
+                [1.0, w2 + w3, w2 * w3],
+                sample_rate,
+            ));
+        }
+
+        let gain: f32 = filters
+            .iter()
+            .map(|f| f.gain_at(1000.0, sample_rate))
+            .product();
+        filters[0].scale(1.0 / gain);
+        filters
+    }
+}
+
+impl TimeWeighting {
+    /// The letter used to denote the time weighting.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeWeighting::Fast => "F",
+            TimeWeighting::Slow => "S",
+            TimeWeighting::Impulse => "I",
+        }
+    }
+
+    /// The (rise, decay) time constants in seconds.
+    fn time_constants(&self) -> (f32, f32) {
+        match self {
+            TimeWeighting::Fast => (0.125, 0.125),
+            TimeWeighting::Slow => (1.0, 1.0),
+            TimeWeighting::Impulse => (0.035, 1.5),
+        }
+    }
+}
+
+/// The measurement state of an [`SplMeter`], fed with audio samples.
+#[derive(Debug, Clone)]
+pub struct SplMeterState {
+    sample_rate: f32,
+    frequency_weighting: FrequencyWeighting,
+    time_weighting: TimeWeighting,
+    /// The level in dB SPL that corresponds to a full scale sine wave.
+    calibration_db: f32,
+    filters: Vec<Biquad>,
+    /// Exponentially time-weighted mean square of the weighted signal.
+    mean_square: f32,
+    /// The highest level since the last reset, in dB SPL.
+    max_level_db: f32,
+}
+
+impl SplMeterState {
+    /// The default level in dB SPL of a full scale sine wave.
+    pub const DEFAULT_CALIBRATION_DB: f32 = 120.0;
+
+    /// Creates a new state measuring A-weighted, fast level at `sample_rate`.
+    pub fn new(sample_rate: f32) -> Self {
+        let frequency_weighting = FrequencyWeighting::default();
+        SplMeterState {
+            sample_rate,
+            frequency_weighting,
+            time_weighting: TimeWeighting::default(),
+            calibration_db: Self::DEFAULT_CALIBRATION_DB,
+            filters: frequency_weighting.filters(sample_rate),
+            mean_square: 0.0,
+            max_level_db: f32::NEG_INFINITY,
+        }
+    }
+
+    /// Sets the frequency weighting.
+    pub fn set_frequency_weighting(&mut self, weighting: FrequencyWeighting) {
+        self.frequency_weighting = weighting;
+        self.filters = weighting.filters(self.sample_rate);
+    }
+
+    /// Sets the time weighting.
+    pub fn set_time_weighting(&mut self, weighting: TimeWeighting) {
+        self.time_weighting = weighting;
+    }
+
+    /// Sets the level in dB SPL that a full scale sine wave corresponds to.
+    ///
+    /// Use the calibration of the measurement microphone, e.g. from a reference level measured
+    /// with a sound level calibrator.
+    pub fn set_calibration_db(&mut self, calibration_db: f32) {
+        self.calibration_db = calibration_db;
+    }
+
+    /// The frequency weighting in use.
+    pub fn frequency_weighting(&self) -> FrequencyWeighting {
+        self.frequency_weighting
+    }
+
+    /// The time weighting in use.
+    pub fn time_weighting(&self) -> TimeWeighting {
+        self.time_weighting
+    }
+
+    /// Feeds audio samples into the meter.
+    ///
+    /// The maximum level is tracked per sample, so it doesn't depend on the size of the blocks.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn process(&mut self, samples: &[f32]) {
+        let (rise, decay) = self.time_weighting.time_constants();
+        let coefficient = |tau: f32| 1.0 - (-1.0 / (tau * self.sample_rate)).exp();
+        let (rise, decay) = (coefficient(rise), coefficient(decay));
+
+        let mut max_mean_square = self.mean_square;
+        for &sample in samples {
+            let weighted = self
+                .filters
+                .iter_mut()
+                .fold(sample, |x, f| f.process_sample(x));
+            let square = weighted * weighted;
+            let coefficient = if square > self.mean_square {
+                rise
+            } else {
+                decay
+            };
+            self.mean_square += coefficient * (square - self.mean_square);
+            max_mean_square = max_mean_square.max(self.mean_square);
+        }
+        self.max_level_db = self.max_level_db.max(self.mean_square_db(max_mean_square));
+    }
+
+    /// The current level in dB SPL.
+    pub fn level_db(&self) -> f32 {
+        self.mean_square_db(self.mean_square)
+    }
+
+    /// Converts a mean square of the weighted signal to a level in dB SPL.
+    fn mean_square_db(&self, mean_square: f32) -> f32 {
+        // A full scale sine has a mean square of 0.5.
+        10.0 * (2.0 * mean_square).max(1e-20).log10() + self.calibration_db
+    }
+
+    /// The highest level since the last reset in dB SPL.
+    pub fn max_level_db(&self) -> f32 {
+        self.max_level_db
+    }
+
+    /// Resets the maximum level.
+    pub fn reset_max(&mut self) {
+        self.max_level_db = f32::NEG_INFINITY;
+    }
+}
+
+/// A widget showing a sound pressure level as a numeric readout with a bar below it.
+#[derive(Debug, Clone)]
+pub struct SplMeter<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The level at the left end of the bar in dB SPL.
+    min_db: f32,
+    /// The level at the right end of the bar in dB SPL.
+    max_db: f32,
+    /// The color of the bar.
+    color: Color,
+}
+
+impl Default for SplMeter<'_> {
+    fn default() -> Self {
+        SplMeter {
+            block: None,
+            min_db: 30.0,
+            max_db: 130.0,
+            color: Color::Green,
+        }
+    }
+}
+
+impl<'a> SplMeter<'a> {
+    /// Creates a new `SplMeter` widget with a range of 30 to 130 dB SPL.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the range of the bar in dB SPL.
+    pub fn range(mut self, min_db: f32, max_db: f32) -> Self {
+        self.min_db = min_db;
+        self.max_db = max_db;
+        self
+    }
+
+    /// Sets the color of the bar.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Surrounds the `SplMeter` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    fn render_bar(&self, level_db: f32, area: Rect, buf: &mut Buffer) {
+        let ratio = ((level_db - self.min_db) / (self.max_db - self.min_db)).clamp(0.0, 1.0);
+        let scaled = ratio * area.width as f32;
+        let full = scaled.floor() as u16;
+        let eighths = ((scaled - full as f32) * 8.0) as usize;
+        let partial = HORIZONTAL_PARTIAL_BLOCKS[eighths];
+
+        for y in area.top()..area.bottom() {
+            for x in 0..full {
+                buf[(area.left() + x, y)]
+                    .set_fg(self.color)
+                    .set_symbol(ratatui::symbols::block::FULL);
+            }
+            if !partial.is_empty() && full < area.width {
+                buf[(area.left() + full, y)]
+                    .set_fg(self.color)
+                    .set_symbol(partial);
+            }
+        }
+    }
+}
+
+impl StatefulWidget for SplMeter<'_> {
+    type State = SplMeterState;
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.height < 2 {
+            return;
+        }
+
+        let [readout_area, bar_area, max_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let level_db = state.level_db();
+        BigNumber::new(format!("{:.1}", level_db))
+            .unit(format!(
+                "dB({}) {}",
+                state.frequency_weighting.label(),
+                state.time_weighting.label()
+            ))
+            .style(Style::new().bold())
+            .render(readout_area, buf);
+
+        self.render_bar(level_db, bar_area, buf);
+
+        if state.max_level_db.is_finite() {
+            Paragraph::new(format!("Max {:.1}", state.max_level_db))
+                .alignment(Alignment::Right)
+                .render(max_area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The response of the weighting in dB at `frequency`.
+    fn response_db(weighting: FrequencyWeighting, frequency: f32, sample_rate: f32) -> f32 {
+        let gain: f32 = weighting
+            .filters(sample_rate)
+            .iter()
+            .map(|f| f.gain_at(frequency, sample_rate))
+            .product();
+        20.0 * gain.log10()
+    }
+
+    fn assert_response(weighting: FrequencyWeighting, expected: [(f32, f32, f32); 3]) {
+        for sample_rate in [44100.0, 48000.0] {
+            for (frequency, expected_db, tolerance_db) in expected {
+                let db = response_db(weighting, frequency, sample_rate);
+                assert!(
+                    (db - expected_db).abs() <= tolerance_db,
+                    "{weighting:?} at {frequency} Hz and {sample_rate} Hz: {db} dB"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn a_weighting_matches_iec_61672() {
+        // Nominal values from IEC 61672-1, with tolerances tighter than class 1.
+        assert_response(
+            FrequencyWeighting::A,
+            [
+                (100.0, -19.1, 0.1),
+                (1000.0, 0.0, 0.01),
+                (10_000.0, -2.5, 1.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn c_weighting_matches_iec_61672() {
+        assert_response(
+            FrequencyWeighting::C,
+            [
+                (100.0, -0.3, 0.1),
+                (1000.0, 0.0, 0.01),
+                (10_000.0, -4.4, 1.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn z_weighting_is_flat() {
+        assert!(FrequencyWeighting::Z.filters(48000.0).is_empty());
+    }
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    /// `seconds` of a full scale 1 kHz sine.
+    fn tone(seconds: f32) -> Vec<f32> {
+        (0..(seconds * SAMPLE_RATE) as usize)
+            .map(|n| (2.0 * std::f32::consts::PI * 1000.0 * n as f32 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    fn silence(seconds: f32) -> Vec<f32> {
+        vec![0.0; (seconds * SAMPLE_RATE) as usize]
+    }
+
+    #[test]
+    fn steady_tone_reads_calibration_level() {
+        let mut state = SplMeterState::new(SAMPLE_RATE);
+        state.process(&tone(2.0));
+        assert!(
+            (state.level_db() - 120.0).abs() < 0.1,
+            "{}",
+            state.level_db()
+        );
+    }
+
+    #[test]
+    fn decay_rates_match_iec_61672() {
+        // Nominal decay rates in dB per second.
+        for (weighting, rate) in [
+            (TimeWeighting::Fast, 34.7),
+            (TimeWeighting::Slow, 4.3),
+            (TimeWeighting::Impulse, 2.9),
+        ] {
+            let mut state = SplMeterState::new(SAMPLE_RATE);
+            state.set_time_weighting(weighting);
+            state.process(&tone(8.0));
+            state.process(&silence(0.1));
+            let start_db = state.level_db();
+            state.process(&silence(0.2));
+            let measured = (start_db - state.level_db()) / 0.2;
+            assert!(
+                (measured - rate).abs() < rate * 0.02,
+                "{weighting:?}: {measured} dB/s"
+            );
+        }
+    }
+
+    #[test]
+    fn max_level_catches_peaks_within_a_block() {
+        let burst = [tone(0.5), silence(1.0)].concat();
+        let mut whole = SplMeterState::new(SAMPLE_RATE);
+        whole.process(&burst);
+        let mut chunked = SplMeterState::new(SAMPLE_RATE);
+        for block in burst.chunks(64) {
+            chunked.process(block);
+        }
+        // The level has long decayed by the end of the block, but the maximum holds the peak.
+        assert!(whole.level_db() < 90.0);
+        assert!(whole.max_level_db() > 119.0, "{}", whole.max_level_db());
+        assert!((whole.max_level_db() - chunked.max_level_db()).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{geometry::RtaGeometry, rendering::ChromeCache, theme::Theme};
+
+/// Statistics of one render of an [`crate::RTA`], collected when enabled with
+/// [`RTAState::collect_render_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    /// How long rendering took.
+    pub duration: Duration,
+    /// The number of cells in the widget's area that aren't empty after rendering.
+    pub cells_drawn: usize,
+    /// The number of bands that had room to be drawn.
+    pub bands_rendered: usize,
+    /// Whether the axes and labels were reused from the previous frame.
+    pub chrome_cached: bool,
+}
+
+/// State of an [`crate::RTA`] widget that persists between frames.
+///
+/// Render the widget with [`ratatui::widgets::StatefulWidget`] to use features that depend on
+/// what happened in previous frames, such as alerts that stay visible for a while.
+#[derive(Debug, Clone, Default)]
+pub struct RTAState {
+    /// When the most recent alert was triggered.
+    alert_triggered_at: Option<Instant>,
+    /// The band shown as the peak, and when it became the peak.
+    peak_band: Option<(usize, Instant)>,
+    /// How long a band stays the peak band before a louder band can take over.
+    peak_band_hold: Duration,
+    /// The highest level in dB of each band since the last reset.
+    band_maxima: Vec<f32>,
+    /// The recent peaks of each band, for peak dots.
+    peak_dots: Vec<PeakDots>,
+    /// When the session started, set on the first render.
+    session_started_at: Option<Instant>,
+    /// When the user was last active, set on the first render.
+    last_activity: Option<Instant>,
+    /// The smoothed bar heights shown in ambient mode.
+    ambient_values: Vec<f32>,
+    /// The theme switched to with [`RTAState::set_theme`], if any.
+    theme: Option<Theme>,
+    /// The theme shown before the last switch, when the switch happened and how long the crossfade
+    /// from it takes.
+    theme_transition: Option<(Theme, Instant, Duration)>,
+    /// The start of the first software blink period, set on the first blink.
+    blink_epoch: Option<Instant>,
+    /// When the signal dropped below the idle threshold, if it is currently below it.
+    silent_since: Option<Instant>,
+    /// The level selected with [`RTAState::select_at`] or [`RTAState::select_db`], if any.
+    selected_db: Option<f32>,
+    /// Where the bars were drawn in the last render, if there was room for them.
+    pub(crate) geometry: Option<RtaGeometry>,
+    /// Whether to collect statistics of each render.
+    collect_render_stats: bool,
+    /// Statistics of the last render, if collected.
+    pub(crate) render_stats: Option<RenderStats>,
+    /// The axes and labels rendered in the last frame, reused while nothing they depend on
+    /// changed.
+    pub(crate) chrome: ChromeCache,
+}
+
+impl RTAState {
+    /// Creates a new `RTAState`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Triggers an alert, e.g. for a clip detected outside the widget.
+    pub fn trigger_alert(&mut self) {
+        self.alert_triggered_at = Some(Instant::now());
+    }
+
+    /// Ends the current alert immediately.
+    pub fn clear_alert(&mut self) {
+        self.alert_triggered_at = None;
+    }
+
+    /// Sets how long a band stays the peak band, highlighted and shown in the peak readout, before
+    /// a louder band can take over. Defaults to zero, which follows the loudest band every frame.
+    pub fn hold_peak_band(&mut self, hold: Duration) {
+        self.peak_band_hold = hold;
+    }
+
+    /// Records the loudest of `band_count` bands in this frame and returns the band to show as the
+    /// peak.
+    pub(crate) fn update_peak_band(
+        &mut self,
+        loudest: Option<usize>,
+        band_count: usize,
+    ) -> Option<usize> {
+        let Some(loudest) = loudest else {
+            self.peak_band = None;
+            return None;
+        };
+        match self.peak_band {
+            Some((index, since))
+                if index == loudest
+                    || (index < band_count && since.elapsed() < self.peak_band_hold) =>
+            {
+                Some(index)
+            }
+            _ => {
+                self.peak_band = Some((loudest, Instant::now()));
+                Some(loudest)
+            }
+        }
+    }
+
+    /// The highest level in dB each band reached since the first render or the last reset.
+    pub fn band_maxima(&self) -> &[f32] {
+        &self.band_maxima
+    }
+
+    /// Restarts tracking the band maxima.
+    pub fn reset_band_maxima(&mut self) {
+        self.band_maxima.clear();
+    }
+
+    /// Raises the band maxima to the levels in dB of this frame. Changing the number of bands
+    /// restarts tracking.
+    pub(crate) fn update_band_maxima(&mut self, levels: impl ExactSizeIterator<Item = f32>) {
+        if self.band_maxima.len() != levels.len() {
+            self.band_maxima = vec![f32::NEG_INFINITY; levels.len()];
+        }
+        for (maximum, db) in self.band_maxima.iter_mut().zip(levels) {
+            if db > *maximum {
+                *maximum = db;
+            }
+        }
+    }
+
+    /// Records the levels in dB of this frame for the peak dots, keeping the last `count` peaks of
+    /// each band. Changing the number of bands starts over.
+    pub(crate) fn update_peak_dots(&mut self, levels: &[f32], count: usize) {
+        if self.peak_dots.len() != levels.len() {
+            self.peak_dots = vec![PeakDots::default(); levels.len()];
+        }
+        for (dots, &db) in self.peak_dots.iter_mut().zip(levels) {
+            dots.update(db, count);
+        }
+    }
+
+    /// The recent peaks in dB of each band, most recent first.
+    pub(crate) fn peak_dots(&self) -> Vec<Vec<f32>> {
+        self.peak_dots
+            .iter()
+            .map(|dots| dots.peaks.iter().copied().collect())
+            .collect()
+    }
+
+    /// How long the session has been running, since the first render or the last
+    /// [`RTAState::restart_session`].
+    pub fn session_time(&mut self) -> Duration {
+        self.session_started_at
+            .get_or_insert_with(Instant::now)
+            .elapsed()
+    }
+
+    /// Starts a new session, e.g. when a recording starts, restarting the session time.
+    pub fn restart_session(&mut self) {
+        self.session_started_at = Some(Instant::now());
+    }
+
+    /// Records user activity, e.g. a keypress, leaving ambient mode.
+    pub fn record_activity(&mut self) {
+        self.last_activity = Some(Instant::now());
+    }
+
+    /// Whether there was no user activity for at least `timeout`. The time counts from the first
+    /// render if there was no activity yet, so this is `false` before the first render.
+    pub fn is_inactive(&self, timeout: Duration) -> bool {
+        self.last_activity
+            .is_some_and(|last_activity| last_activity.elapsed() >= timeout)
+    }
+
+    /// Starts counting the time without user activity on the first render.
+    pub(crate) fn start_activity_timer(&mut self) {
+        self.last_activity.get_or_insert_with(Instant::now);
+    }
+
+    /// Moves the smoothed ambient bar heights a step towards `targets` and returns them. Changing
+    /// the number of bands starts over from the targets.
+    pub(crate) fn smooth_ambient(&mut self, targets: &[f32]) -> &[f32] {
+        /// The fraction of the distance to the target covered each frame.
+        const SMOOTHING: f32 = 0.2;
+
+        if self.ambient_values.len() != targets.len() {
+            self.ambient_values = targets.to_vec();
+        }
+        for (value, target) in self.ambient_values.iter_mut().zip(targets) {
+            *value += (target - *value) * SMOOTHING;
+        }
+        &self.ambient_values
+    }
+
+    /// Switches the theme of the widget, crossfading from the theme shown so far over
+    /// `transition`, or immediately if it is zero or no theme was set yet.
+    ///
+    /// The app must redraw during the transition for the crossfade to show.
+    pub fn set_theme(&mut self, theme: Theme, transition: Duration) {
+        self.theme_transition = self
+            .current_theme()
+            .filter(|_| !transition.is_zero())
+            .map(|from| (from, Instant::now(), transition));
+        self.theme = Some(theme);
+    }
+
+    /// The theme switched to with [`RTAState::set_theme`], if any.
+    pub fn theme(&self) -> Option<Theme> {
+        self.theme
+    }
+
+    /// The theme to show now, partway between the previous and the current theme during a
+    /// transition.
+    pub(crate) fn current_theme(&self) -> Option<Theme> {
+        let theme = self.theme?;
+        Some(match self.theme_transition {
+            Some((from, started_at, transition)) if started_at.elapsed() < transition => from
+                .blend(
+                    &theme,
+                    started_at.elapsed().as_secs_f32() / transition.as_secs_f32(),
+                ),
+            _ => theme,
+        })
+    }
+
+    /// Whether a software blink with the given `period` is in its visible half.
+    pub(crate) fn blink_visible(&mut self, period: Duration) -> bool {
+        let elapsed = self.blink_epoch.get_or_insert_with(Instant::now).elapsed();
+        period.is_zero() || (elapsed.as_nanos() / period.as_nanos()).is_multiple_of(2)
+    }
+
+    /// Records whether the signal is currently silent.
+    pub(crate) fn update_silence(&mut self, silent: bool) {
+        if !silent {
+            self.silent_since = None;
+        } else if self.silent_since.is_none() {
+            self.silent_since = Some(Instant::now());
+        }
+    }
+
+    /// Whether the signal has been silent for at least `timeout`.
+    pub fn is_idle(&self, timeout: Duration) -> bool {
+        self.silent_since
+            .is_some_and(|silent_since| silent_since.elapsed() >= timeout)
+    }
+
+    /// Whether an alert was triggered within the last `hold` duration.
+    pub fn is_alerting(&self, hold: Duration) -> bool {
+        self.alert_triggered_at
+            .is_some_and(|triggered_at| triggered_at.elapsed() < hold)
+    }
+
+    /// Selects the level at a terminal position, e.g. of a mouse click, to draw a reference line
+    /// there.
+    ///
+    /// The position must be on a row of the bars, from the dB axis to the last band. Returns the
+    /// selected level in dB, or `None` if the position is outside, in which case the selection is
+    /// unchanged.
+    pub fn select_at(&mut self, column: u16, row: u16) -> Option<f32> {
+        let geometry = self.geometry.as_ref()?;
+        if column >= geometry.bars_area().right() {
+            return None;
+        }
+        let db = geometry.db_at(row)?;
+        self.selected_db = Some(db);
+        Some(db)
+    }
+
+    /// Selects a level in dB to draw a reference line at, or clears the selection with `None`.
+    pub fn select_db(&mut self, db: Option<f32>) {
+        self.selected_db = db;
+    }
+
+    /// The currently selected level in dB, if any.
+    pub fn selected_db(&self) -> Option<f32> {
+        self.selected_db
+    }
+
+    /// Where the bars were drawn in the last render, and how frequencies and levels map to cells,
+    /// e.g. for mouse handling. `None` before the first render or if there was no room for the
+    /// meter.
+    pub fn geometry(&self) -> Option<&RtaGeometry> {
+        self.geometry.as_ref()
+    }
+
+    /// Sets whether to collect statistics of each render, e.g. to find out which widget of a large
+    /// dashboard is slow. Collecting adds a pass over the widget's area to every render.
+    pub fn collect_render_stats(&mut self, collect: bool) {
+        self.collect_render_stats = collect;
+        if !collect {
+            self.render_stats = None;
+        }
+    }
+
+    /// Whether statistics of each render are collected.
+    pub(crate) fn collects_render_stats(&self) -> bool {
+        self.collect_render_stats
+    }
+
+    /// Statistics of the last render, if collected with [`RTAState::collect_render_stats`].
+    pub fn render_stats(&self) -> Option<RenderStats> {
+        self.render_stats
+    }
+}
+
+/// The recent peaks of one band: the levels at which it stopped rising and started to fall.
+#[derive(Debug, Clone)]
+struct PeakDots {
+    /// The level in dB in the previous frame.
+    previous_db: f32,
+    /// Whether the level was rising in the previous frame.
+    rising: bool,
+    /// The recent peaks in dB, most recent first.
+    peaks: VecDeque<f32>,
+}
+
+impl Default for PeakDots {
+    fn default() -> Self {
+        PeakDots {
+            previous_db: f32::NEG_INFINITY,
+            rising: false,
+            peaks: VecDeque::new(),
+        }
+    }
+}
+
+impl PeakDots {
+    /// Records the level of this frame, adding the previous level as a peak if the band started
+    /// to fall, and keeps at most `count` peaks.
+    fn update(&mut self, db: f32, count: usize) {
+        if db.is_nan() {
+            return;
+        }
+        if db < self.previous_db && self.rising {
+            self.peaks.push_front(self.previous_db);
+        }
+        if db != self.previous_db {
+            self.rising = db > self.previous_db;
+        }
+        self.previous_db = db;
+        self.peaks.truncate(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inactivity_counts_from_first_render() {
+        let mut state = RTAState::new();
+        assert!(!state.is_inactive(Duration::ZERO));
+        state.start_activity_timer();
+        assert!(state.is_inactive(Duration::ZERO));
+        assert!(!state.is_inactive(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn activity_restarts_the_inactivity_timer() {
+        let mut state = RTAState::new();
+        state.record_activity();
+        assert!(state.is_inactive(Duration::ZERO));
+        assert!(!state.is_inactive(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn ambient_values_approach_targets() {
+        let mut state = RTAState::new();
+        assert_eq!(state.smooth_ambient(&[0.5, 1.0]), [0.5, 1.0]);
+        // Each step covers a fifth of the distance to the target.
+        assert_eq!(state.smooth_ambient(&[1.0, 0.0]), [0.6, 0.8]);
+        let values = state.smooth_ambient(&[1.0, 0.0]);
+        assert!((values[0] - 0.68).abs() < 1e-6 && (values[1] - 0.64).abs() < 1e-6);
+        // Changing the number of bands starts over from the targets.
+        assert_eq!(state.smooth_ambient(&[0.25]), [0.25]);
+    }
+}
@@ -0,0 +1,116 @@
+use ratatui::style::Color;
+
+/// The colors of an `RTA` around its bands, e.g. for switching between light and dark terminals.
+///
+/// Set it with [`crate::RTA::theme`], or switch it at runtime with a crossfade through
+/// [`crate::RTAState::set_theme`]. Crossfades blend RGB colors smoothly and switch other colors
+/// halfway through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(deny_unknown_fields))]
+pub struct Theme {
+    /// The background of the widget, replacing the terminal's default background.
+    #[cfg_attr(feature = "config", serde(with = "crate::config::color"))]
+    pub background: Color,
+    /// The color of the axes, labels and borders.
+    #[cfg_attr(feature = "config", serde(with = "crate::config::color"))]
+    pub foreground: Color,
+    /// The color of all bars, or `None` to keep the colors of the bands.
+    #[cfg_attr(
+        feature = "config",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "crate::config::optional_color"
+        )
+    )]
+    pub bars: Option<Color>,
+}
+
+impl Theme {
+    /// Light text on a near-black background.
+    pub const fn dark() -> Self {
+        Theme {
+            background: Color::Rgb(16, 16, 16),
+            foreground: Color::Rgb(230, 230, 230),
+            bars: None,
+        }
+    }
+
+    /// Dark text on a near-white background.
+    pub const fn light() -> Self {
+        Theme {
+            background: Color::Rgb(250, 250, 250),
+            foreground: Color::Rgb(30, 30, 30),
+            bars: None,
+        }
+    }
+
+    /// Detects whether the terminal has a light or dark background from the `COLORFGBG`
+    /// environment variable set by many terminals, e.g. `15;0` for white on black.
+    ///
+    /// The detected theme keeps the terminal's background and only picks black or white text, so
+    /// the axes and labels stay visible. Returns `None` if the background is unknown.
+    #[cfg(feature = "detect-background")]
+    pub fn detect() -> Option<Theme> {
+        Self::from_colorfgbg(&std::env::var("COLORFGBG").ok()?)
+    }
+
+    /// The theme for a terminal with the given `COLORFGBG` value, whose last field is the palette
+    /// index of the background.
+    #[cfg(feature = "detect-background")]
+    pub fn from_colorfgbg(value: &str) -> Option<Theme> {
+        let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+        let light = matches!(background, 7 | 9..=15);
+        Some(Theme {
+            background: Color::Reset,
+            foreground: if light { Color::Black } else { Color::White },
+            bars: None,
+        })
+    }
+
+    /// The theme an `RTA` starts with: the detected one on light terminals with the
+    /// `detect-background` feature, and none otherwise. Detection runs once per process.
+    pub(crate) fn terminal_default() -> Option<Theme> {
+        #[cfg(feature = "detect-background")]
+        {
+            static DETECTED: std::sync::OnceLock<Option<Theme>> = std::sync::OnceLock::new();
+            *DETECTED
+                .get_or_init(|| Self::detect().filter(|theme| theme.foreground == Color::Black))
+        }
+        #[cfg(not(feature = "detect-background"))]
+        None
+    }
+
+    /// The theme a fraction `t` between 0 and 1 of the way from `self` to `other`.
+    pub(crate) fn blend(&self, other: &Theme, t: f32) -> Theme {
+        Theme {
+            background: blend_color(self.background, other.background, t),
+            foreground: blend_color(self.foreground, other.foreground, t),
+            bars: match (self.bars, other.bars) {
+                (Some(from), Some(to)) => Some(blend_color(from, to, t)),
+                (from, to) => {
+                    if t < 0.5 {
+                        from
+                    } else {
+                        to
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// The color a fraction `t` between 0 and 1 of the way from `from` to `to`. RGB colors are
+/// blended, other colors switch halfway.
+pub(crate) fn blend_color(from: Color, to: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let blend = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Color::Rgb(blend(r1, r2), blend(g1, g2), blend(b1, b2))
+        }
+        _ if t >= 0.5 => to,
+        _ => from,
+    }
+}
@@ -0,0 +1,562 @@
+use ratatui::style::Color;
+
+use crate::rta::Band;
+
+/// A single point of a measured frequency response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrequencyPoint {
+    /// The frequency in Hz.
+    pub frequency: f32,
+    /// The magnitude in dB.
+    pub magnitude_db: f32,
+    /// The phase in degrees, if known.
+    pub phase_deg: Option<f32>,
+}
+
+impl FrequencyPoint {
+    /// Creates a new point without phase information.
+    pub fn new(frequency: f32, magnitude_db: f32) -> Self {
+        FrequencyPoint {
+            frequency,
+            magnitude_db,
+            phase_deg: None,
+        }
+    }
+
+    /// Sets the phase of the point in degrees.
+    pub fn with_phase(mut self, phase_deg: f32) -> Self {
+        self.phase_deg = Some(phase_deg);
+        self
+    }
+
+    /// Converts the current values of the bands to points of a frequency response.
+    ///
+    /// Bands without a frequency are skipped.
+    pub fn from_bands(bands: &[Band], min_db: f32) -> Vec<FrequencyPoint> {
+        let mut points: Vec<FrequencyPoint> = bands
+            .iter()
+            .filter_map(|band| {
+                band.frequency
+                    .map(|f| FrequencyPoint::new(f as f32, band.get_db(min_db)))
+            })
+            .collect();
+        points.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        points
+    }
+
+    /// The point as a complex amplitude, assuming zero phase when the phase is unknown.
+    fn to_complex(self) -> (f32, f32) {
+        let amplitude = 10_f32.powf(self.magnitude_db / 20.0);
+        let (sin, cos) = self.phase_deg.unwrap_or(0.0).to_radians().sin_cos();
+        (amplitude * cos, amplitude * sin)
+    }
+
+    fn from_complex(frequency: f32, re: f32, im: f32, with_phase: bool) -> Self {
+        FrequencyPoint {
+            frequency,
+            magnitude_db: (20.0 * re.hypot(im).log10()).max(-200.0),
+            phase_deg: with_phase.then(|| im.atan2(re).to_degrees()),
+        }
+    }
+}
+
+/// Where a stored trace came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKind {
+    /// Continuously updated from the live measurement.
+    Live,
+    /// A snapshot of a live measurement.
+    Captured,
+    /// Loaded from a file.
+    Imported,
+    /// The result of a math operation on other traces.
+    Computed,
+}
+
+/// A named frequency response kept in a [`TraceStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    /// The name shown in the trace list.
+    pub name: String,
+    /// Where the trace came from.
+    pub kind: TraceKind,
+    /// The points of the trace, sorted by frequency.
+    pub points: Vec<FrequencyPoint>,
+    /// The color used to draw the trace.
+    pub color: Color,
+    /// Whether the trace is drawn.
+    pub visible: bool,
+}
+
+/// Colors assigned to traces in the order they are added.
+const TRACE_PALETTE: [Color; 8] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::LightBlue,
+    Color::LightRed,
+    Color::White,
+    Color::LightYellow,
+    Color::Blue,
+];
+
+/// A collection of named traces that can be shown, hidden and combined.
+///
+/// Visible traces are drawn on top of the bars with [`crate::RTA::traces`] and can be listed and
+/// toggled with the [`crate::TraceList`] widget.
+#[derive(Debug, Clone, Default)]
+pub struct TraceStore {
+    traces: Vec<Trace>,
+    /// The number of colors handed out so far.
+    colors_assigned: usize,
+}
+
+impl TraceStore {
+    /// Creates an empty `TraceStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a visible trace with the next color of the palette and returns its index.
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        kind: TraceKind,
+        mut points: Vec<FrequencyPoint>,
+    ) -> usize {
+        points.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        let color = TRACE_PALETTE[self.colors_assigned % TRACE_PALETTE.len()];
+        self.colors_assigned += 1;
+        self.traces.push(Trace {
+            name: name.into(),
+            kind,
+            points,
+            color,
+            visible: true,
+        });
+        self.traces.len() - 1
+    }
+
+    /// Stores a snapshot of the current band values as a captured trace and returns its index.
+    pub fn capture(&mut self, name: impl Into<String>, bands: &[Band], min_db: f32) -> usize {
+        self.add(
+            name,
+            TraceKind::Captured,
+            FrequencyPoint::from_bands(bands, min_db),
+        )
+    }
+
+    /// Removes the trace at `index`.
+    pub fn remove(&mut self, index: usize) -> Option<Trace> {
+        (index < self.traces.len()).then(|| self.traces.remove(index))
+    }
+
+    /// All traces in the order they were added.
+    pub fn traces(&self) -> &[Trace] {
+        &self.traces
+    }
+
+    /// The trace at `index`.
+    pub fn get(&self, index: usize) -> Option<&Trace> {
+        self.traces.get(index)
+    }
+
+    /// The trace at `index`, mutably.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Trace> {
+        self.traces.get_mut(index)
+    }
+
+    /// The index of the first trace called `name`.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.traces.iter().position(|trace| trace.name == name)
+    }
+
+    /// The number of stored traces.
+    pub fn len(&self) -> usize {
+        self.traces.len()
+    }
+
+    /// Whether the store holds no traces.
+    pub fn is_empty(&self) -> bool {
+        self.traces.is_empty()
+    }
+
+    /// Replaces the points of the trace at `index`, e.g. to update a live trace.
+    pub fn set_points(&mut self, index: usize, mut points: Vec<FrequencyPoint>) {
+        if let Some(trace) = self.traces.get_mut(index) {
+            points.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+            trace.points = points;
+        }
+    }
+
+    /// Shows or hides the trace at `index`.
+    pub fn set_visible(&mut self, index: usize, visible: bool) {
+        if let Some(trace) = self.traces.get_mut(index) {
+            trace.visible = visible;
+        }
+    }
+
+    /// Toggles the visibility of the trace at `index`.
+    pub fn toggle_visible(&mut self, index: usize) {
+        if let Some(trace) = self.traces.get_mut(index) {
+            trace.visible = !trace.visible;
+        }
+    }
+
+    /// Sets the color of the trace at `index`.
+    pub fn set_color(&mut self, index: usize, color: Color) {
+        if let Some(trace) = self.traces.get_mut(index) {
+            trace.color = color;
+        }
+    }
+
+    /// The traces that are currently shown.
+    pub fn visible(&self) -> impl Iterator<Item = &Trace> {
+        self.traces.iter().filter(|trace| trace.visible)
+    }
+
+    /// Stores the complex difference `a - b` as a new trace and returns its index.
+    ///
+    /// The responses are subtracted as linear amplitudes, using their phase when both traces have
+    /// it. To compare two responses in dB, e.g. a room response against the anechoic speaker
+    /// response, use [`TraceStore::divide`] instead.
+    pub fn subtract(&mut self, a: usize, b: usize, name: impl Into<String>) -> Option<usize> {
+        self.combine(a, b, name, |a, b| {
+            let (re_a, im_a) = a.to_complex();
+            let (re_b, im_b) = b.to_complex();
+            FrequencyPoint::from_complex(
+                a.frequency,
+                re_a - re_b,
+                im_a - im_b,
+                a.phase_deg.zip(b.phase_deg).is_some(),
+            )
+        })
+    }
+
+    /// Stores the quotient `a / b` as a new trace and returns its index.
+    ///
+    /// This is the difference of the magnitudes in dB, and of the phases when both traces have
+    /// them.
+    pub fn divide(&mut self, a: usize, b: usize, name: impl Into<String>) -> Option<usize> {
+        self.combine(a, b, name, |a, b| FrequencyPoint {
+            frequency: a.frequency,
+            magnitude_db: a.magnitude_db - b.magnitude_db,
+            phase_deg: a
+                .phase_deg
+                .zip(b.phase_deg)
+                .map(|(pa, pb)| wrap_phase(pa - pb)),
+        })
+    }
+
+    /// Stores the inverse `1 / a` as a new trace and returns its index.
+    ///
+    /// The magnitude and phase are negated, which turns e.g. a measured response into the
+    /// correction curve that flattens it.
+    pub fn invert(&mut self, a: usize, name: impl Into<String>) -> Option<usize> {
+        let points = self
+            .traces
+            .get(a)?
+            .points
+            .iter()
+            .map(|p| FrequencyPoint {
+                magnitude_db: -p.magnitude_db,
+                phase_deg: p.phase_deg.map(|phase| wrap_phase(-phase)),
+                ..*p
+            })
+            .collect();
+        Some(self.add(name, TraceKind::Computed, points))
+    }
+
+    /// Stores the average of the given traces as a new trace and returns its index.
+    ///
+    /// Magnitudes are averaged in dB at the frequencies of the first trace that are covered by all
+    /// of them. Phases are averaged when all traces have them.
+    pub fn average(&mut self, indices: &[usize], name: impl Into<String>) -> Option<usize> {
+        let traces: Vec<&Trace> = indices
+            .iter()
+            .map(|&i| self.traces.get(i))
+            .collect::<Option<_>>()?;
+        let first = traces.first()?;
+        let count = traces.len() as f32;
+        let points = first
+            .points
+            .iter()
+            .filter_map(|p| {
+                let points = traces
+                    .iter()
+                    .map(|t| interpolate(&t.points, p.frequency))
+                    .collect::<Option<Vec<_>>>()?;
+                let magnitude_db = points.iter().map(|p| p.magnitude_db).sum::<f32>() / count;
+                // Average phases as unit vectors so that e.g. 179° and -179° average to 180°.
+                let phase_deg = points
+                    .iter()
+                    .map(|p| p.phase_deg.map(|phase| phase.to_radians().sin_cos()))
+                    .try_fold((0.0_f32, 0.0_f32), |(sin_sum, cos_sum), phasor| {
+                        phasor.map(|(sin, cos)| (sin_sum + sin, cos_sum + cos))
+                    })
+                    .map(|(sin, cos)| sin.atan2(cos).to_degrees());
+                Some(FrequencyPoint {
+                    frequency: p.frequency,
+                    magnitude_db,
+                    phase_deg,
+                })
+            })
+            .collect();
+        Some(self.add(name, TraceKind::Computed, points))
+    }
+
+    /// Stores `op` applied point by point to `a` and `b` as a new trace and returns its index.
+    ///
+    /// The result is evaluated at the frequencies of `a` that are covered by `b`.
+    fn combine(
+        &mut self,
+        a: usize,
+        b: usize,
+        name: impl Into<String>,
+        op: impl Fn(FrequencyPoint, FrequencyPoint) -> FrequencyPoint,
+    ) -> Option<usize> {
+        let (a, b) = (self.traces.get(a)?, self.traces.get(b)?);
+        let points = a
+            .points
+            .iter()
+            .filter_map(|p| interpolate(&b.points, p.frequency).map(|q| op(*p, q)))
+            .collect();
+        Some(self.add(name, TraceKind::Computed, points))
+    }
+}
+
+/// Wraps a phase in degrees to the range (-180, 180].
+fn wrap_phase(phase_deg: f32) -> f32 {
+    let wrapped = (phase_deg + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 { 180.0 } else { wrapped }
+}
+
+/// Resamples a frequency response to the layout of `bands`, returning its magnitude in dB at each
+/// band.
+///
+/// Each band covers the frequencies from halfway to its lower neighbor to halfway to its upper
+/// neighbor, on a logarithmic axis. The magnitudes of the points within a band are averaged, which
+/// smooths dense curves such as calibration files, and bands without points inside are
+/// interpolated at their center, linearly in dB over a logarithmic frequency axis. `points` must
+/// be sorted by frequency. Bands without a frequency or outside the range of the points are NaN.
+///
+/// ```
+/// use rataudio_rta::{Band, FrequencyPoint, resample_curve};
+///
+/// let points = [
+///     FrequencyPoint::new(900.0, -2.0),
+///     FrequencyPoint::new(1100.0, -4.0),
+///     FrequencyPoint::new(3000.0, 0.0),
+/// ];
+/// let bands = [1000, 1300, 1600, 8000].map(|f| Band::new(0.0, f));
+/// let db = resample_curve(&points, &bands);
+/// assert_eq!(db[0], -3.0);
+/// assert!(db[1] > -4.0 && db[1] < 0.0);
+/// assert_eq!(db[2], 0.0);
+/// assert!(db[3].is_nan());
+/// ```
+pub fn resample_curve(points: &[FrequencyPoint], bands: &[Band]) -> Vec<f32> {
+    let centers: Vec<Option<f32>> = bands
+        .iter()
+        .map(|band| band.frequency.filter(|&f| f > 0).map(f32::from))
+        .collect();
+    (0..centers.len())
+        .map(|i| {
+            let Some(center) = centers[i] else {
+                return f32::NAN;
+            };
+            let below = centers[..i].iter().rev().flatten().next();
+            let above = centers[i + 1..].iter().flatten().next();
+            // Mirror the edge towards the missing neighbor of the first and last band.
+            let (lower, upper) = match (below, above) {
+                (Some(below), Some(above)) => ((below * center).sqrt(), (center * above).sqrt()),
+                (Some(below), None) => ((below * center).sqrt(), center * (center / below).sqrt()),
+                (None, Some(above)) => (center / (above / center).sqrt(), (center * above).sqrt()),
+                (None, None) => (center, center),
+            };
+            // The points are sorted, so the ones inside the band are a contiguous range.
+            let first = points.partition_point(|p| p.frequency < lower);
+            let last = first + points[first..].partition_point(|p| p.frequency < upper);
+            let (sum, count) = points[first..last]
+                .iter()
+                .fold((0.0, 0), |(sum, count), p| {
+                    (sum + p.magnitude_db, count + 1)
+                });
+            if count > 0 {
+                sum / count as f32
+            } else {
+                interpolate_db(points, center).unwrap_or(f32::NAN)
+            }
+        })
+        .collect()
+}
+
+/// Interpolates the magnitude of a frequency response at `frequency`.
+///
+/// Interpolation is linear in dB over a logarithmic frequency axis. `points` must be sorted by
+/// frequency; `None` is returned outside the range they cover.
+pub(crate) fn interpolate_db(points: &[FrequencyPoint], frequency: f32) -> Option<f32> {
+    interpolate(points, frequency).map(|p| p.magnitude_db)
+}
+
+/// Interpolates a frequency response at `frequency`, including the phase when both neighbouring
+/// points have it.
+fn interpolate(points: &[FrequencyPoint], frequency: f32) -> Option<FrequencyPoint> {
+    let first = points.first()?;
+    let last = points.last()?;
+    if frequency < first.frequency || frequency > last.frequency {
+        return None;
+    }
+    let upper = points
+        .partition_point(|p| p.frequency < frequency)
+        .min(points.len() - 1);
+    let hi = points[upper];
+    if upper == 0 || hi.frequency == frequency {
+        return Some(FrequencyPoint { frequency, ..hi });
+    }
+    let lo = points[upper - 1];
+    let t = (frequency / lo.frequency).ln() / (hi.frequency / lo.frequency).ln();
+    Some(FrequencyPoint {
+        frequency,
+        magnitude_db: lo.magnitude_db + t * (hi.magnitude_db - lo.magnitude_db),
+        // Interpolate along the shortest way around the circle.
+        phase_deg: lo
+            .phase_deg
+            .zip(hi.phase_deg)
+            .map(|(lo, hi)| wrap_phase(lo + t * wrap_phase(hi - lo))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_on_log_frequency_axis() {
+        let points = [
+            FrequencyPoint::new(100.0, 0.0),
+            FrequencyPoint::new(400.0, -12.0),
+        ];
+        assert_eq!(interpolate_db(&points, 200.0), Some(-6.0));
+        assert_eq!(interpolate_db(&points, 50.0), None);
+    }
+
+    #[test]
+    fn resamples_dense_curve_to_bands() {
+        // A slope of -3 dB per octave, sampled densely from 10 Hz to 40 kHz.
+        let points: Vec<FrequencyPoint> = (0..6000)
+            .map(|i| {
+                let frequency = 10.0 * 2_f32.powf(i as f32 / 500.0);
+                FrequencyPoint::new(frequency, -3.0 * (frequency / 1000.0).log2())
+            })
+            .collect();
+        let bands: Vec<Band> = crate::layout::ISO_OCTAVE
+            .iter()
+            .map(|&frequency| Band::new(0.0, frequency))
+            .collect();
+        for (db, band) in resample_curve(&points, &bands).iter().zip(&bands) {
+            let expected = -3.0 * (band.frequency.unwrap() as f32 / 1000.0).log2();
+            assert!((db - expected).abs() < 0.1, "{db} != {expected}");
+        }
+    }
+
+    fn trace(points: &[(f32, f32, Option<f32>)]) -> Vec<FrequencyPoint> {
+        points
+            .iter()
+            .map(|&(frequency, magnitude_db, phase_deg)| FrequencyPoint {
+                frequency,
+                magnitude_db,
+                phase_deg,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn subtracting_equal_traces_gives_floor() {
+        let mut store = TraceStore::new();
+        let points = trace(&[(100.0, -6.0, Some(30.0)), (1000.0, 3.0, Some(-90.0))]);
+        let a = store.add("a", TraceKind::Captured, points.clone());
+        let b = store.add("b", TraceKind::Captured, points);
+        let difference = store.subtract(a, b, "a - b").unwrap();
+        let difference = &store.get(difference).unwrap().points;
+        assert_eq!(difference.len(), 2);
+        assert!(difference.iter().all(|p| p.magnitude_db == -200.0));
+        assert!(difference.iter().all(|p| p.phase_deg.is_some()));
+    }
+
+    #[test]
+    fn subtracting_without_phase_uses_magnitudes() {
+        let mut store = TraceStore::new();
+        // Amplitude 1.0 minus amplitude 0.5 is 0.5, i.e. -6.02 dB.
+        let a = store.add("a", TraceKind::Captured, trace(&[(1000.0, 0.0, Some(0.0))]));
+        let b = store.add("b", TraceKind::Captured, trace(&[(1000.0, -6.0206, None)]));
+        let difference = store.subtract(a, b, "a - b").unwrap();
+        let point = store.get(difference).unwrap().points[0];
+        assert!((point.magnitude_db - -6.0206).abs() < 1e-3, "{point:?}");
+        assert_eq!(point.phase_deg, None);
+    }
+
+    #[test]
+    fn dividing_subtracts_db_and_wraps_phase() {
+        let mut store = TraceStore::new();
+        let a = store.add(
+            "a",
+            TraceKind::Captured,
+            trace(&[(1000.0, -3.0, Some(170.0))]),
+        );
+        let b = store.add(
+            "b",
+            TraceKind::Captured,
+            trace(&[(1000.0, -9.0, Some(-170.0))]),
+        );
+        let quotient = store.divide(a, b, "a / b").unwrap();
+        let point = store.get(quotient).unwrap().points[0];
+        assert_eq!(point.magnitude_db, 6.0);
+        assert!((point.phase_deg.unwrap() - -20.0).abs() < 1e-4, "{point:?}");
+    }
+
+    #[test]
+    fn inverting_negates_magnitude_and_phase() {
+        let mut store = TraceStore::new();
+        let points = trace(&[(100.0, -6.0, Some(45.0)), (1000.0, 3.0, None)]);
+        let a = store.add("a", TraceKind::Captured, points);
+        let inverse = store.invert(a, "1 / a").unwrap();
+        let inverse = store.get(inverse).unwrap();
+        assert_eq!(inverse.kind, TraceKind::Computed);
+        assert_eq!(
+            inverse.points,
+            trace(&[(100.0, 6.0, Some(-45.0)), (1000.0, -3.0, None)])
+        );
+    }
+
+    #[test]
+    fn averages_phases_around_the_circle() {
+        let mut store = TraceStore::new();
+        let a = store.add(
+            "a",
+            TraceKind::Captured,
+            trace(&[(1000.0, -2.0, Some(179.0))]),
+        );
+        let b = store.add(
+            "b",
+            TraceKind::Captured,
+            trace(&[(1000.0, -4.0, Some(-179.0))]),
+        );
+        let average = store.average(&[a, b], "average").unwrap();
+        let point = store.get(average).unwrap().points[0];
+        assert_eq!(point.magnitude_db, -3.0);
+        assert!(
+            (point.phase_deg.unwrap().abs() - 180.0).abs() < 1e-3,
+            "{point:?}"
+        );
+    }
+
+    #[test]
+    fn average_of_unknown_trace_is_none() {
+        let mut store = TraceStore::new();
+        let a = store.add("a", TraceKind::Captured, trace(&[(1000.0, 0.0, None)]));
+        assert_eq!(store.average(&[a, 5], "average"), None);
+        assert_eq!(store.average(&[], "average"), None);
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -0,0 +1,80 @@
+use ratatui::{
+    prelude::{Buffer, Rect, Widget},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, StatefulWidget},
+};
+
+use crate::trace::{TraceKind, TraceStore};
+
+/// A list widget showing the traces of a [`TraceStore`] with their visibility and color.
+///
+/// The widget only displays the store; toggling is done by the application with
+/// [`TraceStore::toggle_visible`] on the selected index.
+#[derive(Debug, Clone)]
+pub struct TraceList<'a> {
+    /// The block that surrounds the list, if any.
+    block: Option<Block<'a>>,
+    /// The traces to list.
+    store: &'a TraceStore,
+    /// The index of the highlighted trace, if any.
+    selected: Option<usize>,
+}
+
+impl<'a> TraceList<'a> {
+    /// Creates a new `TraceList` listing the traces of `store`.
+    pub fn new(store: &'a TraceStore) -> Self {
+        TraceList {
+            block: None,
+            store,
+            selected: None,
+        }
+    }
+
+    /// Highlights the trace at `index`.
+    pub fn select(mut self, index: Option<usize>) -> Self {
+        self.selected = index;
+        self
+    }
+
+    /// Surrounds the `TraceList` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    fn kind_label(kind: TraceKind) -> &'static str {
+        match kind {
+            TraceKind::Live => "live",
+            TraceKind::Captured => "capt",
+            TraceKind::Imported => "file",
+            TraceKind::Computed => "calc",
+        }
+    }
+}
+
+impl Widget for TraceList<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let items = self.store.traces().iter().map(|trace| {
+            let checkbox = if trace.visible { "[x] " } else { "[ ] " };
+            let mut name = Span::styled(trace.name.as_str(), Style::new().fg(trace.color));
+            if !trace.visible {
+                name = name.dim();
+            }
+            ListItem::new(Line::from(vec![
+                Span::raw(checkbox),
+                Span::raw("■ ").fg(trace.color),
+                name,
+                Span::raw(format!(" ({})", Self::kind_label(trace.kind))).dim(),
+            ]))
+        });
+
+        let mut list = List::new(items).highlight_style(Style::new().reversed());
+        if let Some(block) = self.block {
+            list = list.block(block);
+        }
+        let mut state = ListState::default().with_selected(self.selected);
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+}
@@ -0,0 +1,123 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Paragraph},
+};
+
+use crate::pitch::Pitch;
+
+/// A tuner widget showing the nearest note and the deviation from it on a needle scale.
+///
+/// The scale spans -50 to +50 cents. The needle turns green when the pitch is within the in-tune
+/// tolerance.
+#[derive(Debug, Clone)]
+pub struct Tuner<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The detected pitch, if any.
+    pitch: Option<Pitch>,
+    /// The deviation in cents within which the pitch counts as in tune.
+    tolerance_cents: f32,
+}
+
+impl<'a> Tuner<'a> {
+    /// Creates a new `Tuner` showing `pitch`, e.g. from [`crate::PitchDetector::detect`].
+    pub fn new(pitch: Option<Pitch>) -> Self {
+        Tuner {
+            block: None,
+            pitch,
+            tolerance_cents: 5.0,
+        }
+    }
+
+    /// Sets the deviation in cents within which the pitch counts as in tune. Defaults to 5 cents.
+    pub fn tolerance(mut self, tolerance_cents: f32) -> Self {
+        self.tolerance_cents = tolerance_cents;
+        self
+    }
+
+    /// Surrounds the `Tuner` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    fn render_scale(&self, area: Rect, buf: &mut Buffer) {
+        let center = area.left() + area.width / 2;
+        for x in area.left()..area.right() {
+            let symbol = if x == center { "┼" } else { "─" };
+            buf[(x, area.top())].set_symbol(symbol).set_fg(Color::Gray);
+        }
+        if area.height > 1 {
+            let labels = Rect::new(area.x, area.y + 1, area.width, 1);
+            Paragraph::new("-50").render(labels, buf);
+            Paragraph::new("0")
+                .alignment(Alignment::Center)
+                .render(labels, buf);
+            Paragraph::new("+50")
+                .alignment(Alignment::Right)
+                .render(labels, buf);
+        }
+    }
+}
+
+impl Widget for Tuner<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.height < 3 || inner.width < 7 {
+            return;
+        }
+
+        let [note_area, needle_area, scale_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(2),
+        ])
+        .areas(inner);
+        self.render_scale(scale_area, buf);
+
+        let Some(pitch) = self.pitch else {
+            Paragraph::new("--")
+                .alignment(Alignment::Center)
+                .style(Style::new().fg(Color::DarkGray))
+                .render(note_area, buf);
+            return;
+        };
+
+        let cents = pitch.cents();
+        let color = if cents.abs() <= self.tolerance_cents {
+            Color::Green
+        } else {
+            Color::Yellow
+        };
+
+        let note_row = Rect {
+            y: note_area.y + note_area.height.saturating_sub(1) / 2,
+            height: note_area.height.min(1),
+            ..note_area
+        };
+        Paragraph::new(format!(
+            "{}  {:+.0} ct  {:.1} Hz",
+            pitch.note_name(),
+            cents,
+            pitch.frequency
+        ))
+        .alignment(Alignment::Center)
+        .style(Style::new().fg(color).bold())
+        .render(note_row, buf);
+
+        let half_width = (needle_area.width - 1) as f32 / 2.0;
+        let offset = (cents / 50.0 * half_width).round() as i32;
+        let x = (needle_area.left() as i32 + half_width.round() as i32 + offset)
+            .clamp(needle_area.left() as i32, needle_area.right() as i32 - 1);
+        buf[(x as u16, needle_area.top())]
+            .set_symbol("▼")
+            .set_fg(color);
+    }
+}
@@ -0,0 +1,309 @@
+use std::f32::consts::PI;
+
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    prelude::{BlockExt, Buffer, Rect, Widget},
+    style::Color,
+    symbols::Marker,
+    widgets::{
+        Block, Paragraph,
+        canvas::{Canvas, Line},
+    },
+};
+
+/// Settings for computing a cumulative spectral decay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecaySettings {
+    /// The number of spectra to compute.
+    pub slices: usize,
+    /// The time between the start of consecutive spectra in milliseconds.
+    pub time_step_ms: f32,
+    /// The length of the analyzed part of the response in milliseconds, from its start. Every
+    /// spectrum ends here, like a gate that keeps out reflections.
+    pub window_ms: f32,
+    /// The lowest frequency of the spectra in Hz.
+    pub min_frequency: f32,
+    /// The highest frequency of the spectra in Hz.
+    pub max_frequency: f32,
+    /// The number of logarithmically spaced frequencies in each spectrum.
+    pub resolution: usize,
+}
+
+impl Default for DecaySettings {
+    fn default() -> Self {
+        DecaySettings {
+            slices: 16,
+            time_step_ms: 0.5,
+            window_ms: 10.0,
+            min_frequency: 200.0,
+            max_frequency: 20000.0,
+            resolution: 96,
+        }
+    }
+}
+
+/// A cumulative spectral decay: successive spectra of an impulse response, each starting a little
+/// later in time and all ending at the same point.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SpectralDecay {
+    /// The frequencies of the spectra in Hz.
+    pub frequencies: Vec<f32>,
+    /// The spectra in dB relative to the overall maximum, ordered from earliest to latest.
+    pub slices: Vec<Vec<f32>>,
+    /// The time between the start of consecutive spectra in milliseconds.
+    pub time_step_ms: f32,
+}
+
+impl SpectralDecay {
+    /// Computes the cumulative spectral decay of an impulse response.
+    ///
+    /// Each slice is the spectrum of the response from `time_step_ms` after the start of the
+    /// previous slice to the end of the window, so later slices show only what is still ringing.
+    /// The tail of each slice is tapered by half a Hann window to limit truncation ripple. Slices
+    /// starting after the end of the window are empty.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn compute(samples: &[f32], sample_rate: f32, settings: &DecaySettings) -> Self {
+        let resolution = settings.resolution.max(2);
+        let ratio = settings.max_frequency / settings.min_frequency;
+        let frequencies: Vec<f32> = (0..resolution)
+            .map(|i| settings.min_frequency * ratio.powf(i as f32 / (resolution - 1) as f32))
+            .collect();
+
+        let window_len = (settings.window_ms / 1000.0 * sample_rate) as usize;
+        let step = (settings.time_step_ms / 1000.0 * sample_rate) as usize;
+
+        // All slices end at the same point, only their start moves later.
+        let end = window_len.min(samples.len());
+        let taper_len = (end / 4).max(1);
+        let mut slices: Vec<Vec<f32>> = (0..settings.slices)
+            .map(|k| {
+                let start = (k * step).min(end);
+                let slice = &samples[start..end];
+                let slice: Vec<f32> = slice
+                    .iter()
+                    .enumerate()
+                    .map(|(n, &s)| {
+                        let from_end = slice.len() - 1 - n;
+                        if from_end < taper_len {
+                            s * (0.5 - 0.5 * (PI * from_end as f32 / taper_len as f32).cos())
+                        } else {
+                            s
+                        }
+                    })
+                    .collect();
+                frequencies
+                    .iter()
+                    .map(|&f| magnitude_db(&slice, f, sample_rate))
+                    .collect()
+            })
+            .collect();
+
+        let max = slices
+            .iter()
+            .flatten()
+            .fold(f32::NEG_INFINITY, |max, &db| max.max(db));
+        if max.is_finite() {
+            slices.iter_mut().flatten().for_each(|db| *db -= max);
+        }
+
+        SpectralDecay {
+            frequencies,
+            slices,
+            time_step_ms: settings.time_step_ms,
+        }
+    }
+}
+
+/// The magnitude of the DFT of `samples` at frequency `f`, in dB.
+fn magnitude_db(samples: &[f32], f: f32, sample_rate: f32) -> f32 {
+    let w = 2.0 * PI * f / sample_rate;
+    let (re, im) = samples
+        .iter()
+        .enumerate()
+        .fold((0.0_f32, 0.0_f32), |(re, im), (n, &s)| {
+            let phase = w * n as f32;
+            (re + s * phase.cos(), im - s * phase.sin())
+        });
+    10.0 * (re * re + im * im).max(1e-20).log10()
+}
+
+/// A widget drawing a [`SpectralDecay`] as a waterfall plot.
+///
+/// The earliest spectrum is drawn in front, at the bottom left, and later spectra are offset up
+/// and to the right and drawn in fading colors.
+#[derive(Debug, Clone)]
+pub struct Waterfall<'a> {
+    /// The block that surrounds the widget, if any.
+    block: Option<Block<'a>>,
+    /// The cumulative spectral decay to plot.
+    decay: &'a SpectralDecay,
+    /// The range of the level axis in dB below the maximum.
+    range_db: f32,
+    /// The color of the earliest spectrum.
+    color: Color,
+}
+
+impl<'a> Waterfall<'a> {
+    /// Creates a new `Waterfall` widget plotting `decay`.
+    pub fn new(decay: &'a SpectralDecay) -> Self {
+        Waterfall {
+            block: None,
+            decay,
+            range_db: 40.0,
+            color: Color::Rgb(255, 220, 0),
+        }
+    }
+
+    /// Sets the range of the level axis in dB below the maximum.
+    pub fn range_db(mut self, range_db: f32) -> Self {
+        self.range_db = range_db.abs().max(1.0);
+        self
+    }
+
+    /// Sets the color of the earliest spectrum. Later spectra fade towards black.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Surrounds the `Waterfall` widget with a [`Block`].
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// The color of slice `k`, fading with age when the base color is RGB.
+    fn slice_color(&self, k: usize) -> Color {
+        let Color::Rgb(r, g, b) = self.color else {
+            return self.color;
+        };
+        let slices = self.decay.slices.len().max(1) as f32;
+        let fade = 1.0 - 0.75 * k as f32 / slices;
+        let scale = |c: u8| (c as f32 * fade) as u8;
+        Color::Rgb(scale(r), scale(g), scale(b))
+    }
+
+    fn render_plot(&self, area: Rect, buf: &mut Buffer) {
+        let slices = self.decay.slices.len();
+        let points = self.decay.frequencies.len();
+        if slices == 0 || points < 2 {
+            return;
+        }
+
+        // Later slices are shifted by up to a third of the plot in both directions.
+        let x_offset = 0.5 / slices as f64;
+        let y_offset = 0.5 / slices as f64;
+        let range_db = self.range_db as f64;
+
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([0.0, 1.5])
+            .y_bounds([0.0, 1.5])
+            .paint(|ctx| {
+                // Draw from back to front so the earliest spectrum ends up on top.
+                for (k, slice) in self.decay.slices.iter().enumerate().rev() {
+                    let color = self.slice_color(k);
+                    let point = |i: usize| {
+                        let x = i as f64 / (points - 1) as f64 + k as f64 * x_offset;
+                        let level = ((slice[i] as f64 + range_db) / range_db).clamp(0.0, 1.0);
+                        (x, level + k as f64 * y_offset)
+                    };
+                    for i in 1..points {
+                        let (x1, y1) = point(i - 1);
+                        let (x2, y2) = point(i);
+                        ctx.draw(&Line::new(x1, y1, x2, y2, color));
+                    }
+                }
+            })
+            .render(area, buf);
+    }
+
+    fn render_labels(&self, area: Rect, buf: &mut Buffer) {
+        let (Some(first), Some(last)) = (
+            self.decay.frequencies.first(),
+            self.decay.frequencies.last(),
+        ) else {
+            return;
+        };
+        Paragraph::new(format!("{:.0} Hz", first))
+            .alignment(Alignment::Left)
+            .render(area, buf);
+        Paragraph::new(format!("{:.1} ms/slice", self.decay.time_step_ms))
+            .alignment(Alignment::Center)
+            .render(area, buf);
+        Paragraph::new(format!("{:.0} Hz", last))
+            .alignment(Alignment::Right)
+            .render(area, buf);
+    }
+}
+
+impl Widget for Waterfall<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.block.as_ref() {
+            block.render(area, buf);
+        }
+
+        let inner = self.block.inner_if_some(area);
+        if inner.height < 2 {
+            return;
+        }
+
+        let [plot_area, label_area] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(inner);
+        self.render_plot(plot_area, buf);
+        self.render_labels(label_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1 kHz resonance decaying with a time constant of 5 ms.
+    fn resonance(sample_rate: f32) -> Vec<f32> {
+        (0..4800)
+            .map(|n| {
+                let t = n as f32 / sample_rate;
+                (-t / 0.005).exp() * (2.0 * PI * 1000.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn settings() -> DecaySettings {
+        DecaySettings {
+            slices: 60,
+            time_step_ms: 0.5,
+            window_ms: 20.0,
+            min_frequency: 1000.0,
+            max_frequency: 2000.0,
+            resolution: 2,
+        }
+    }
+
+    #[test]
+    fn resonance_decays_at_its_time_constant() {
+        let decay = SpectralDecay::compute(&resonance(48000.0), 48000.0, &settings());
+        let at_resonance: Vec<f32> = decay.slices.iter().map(|slice| slice[0]).collect();
+        assert!(at_resonance[0].abs() < 0.1);
+        // An exponential decay with a 5 ms time constant falls 8.69 dB every 5 ms.
+        let expected = -8.69 * 4.0 * 0.5 / 5.0;
+        assert!((at_resonance[4] - expected).abs() < 0.5, "{at_resonance:?}");
+        assert!(
+            at_resonance
+                .windows(2)
+                .take(20)
+                .all(|pair| pair[1] < pair[0])
+        );
+    }
+
+    #[test]
+    fn slices_end_at_the_window() {
+        let decay = SpectralDecay::compute(&resonance(48000.0), 48000.0, &settings());
+        // The slices starting at 20 ms and later are past the end of the window.
+        assert!(decay.slices[40..].iter().flatten().all(|&db| db < -150.0));
+        // A slice starting just before the end holds only the tapered tail of the window.
+        assert!(decay.slices[39][0] < decay.slices[30][0] - 20.0);
+    }
+}
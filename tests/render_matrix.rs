@@ -0,0 +1,211 @@
+//! Renders the RTA across a matrix of terminal sizes and band counts, asserting that nothing
+//! panics and that the axes and labels line up.
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::{Position, Rect},
+    widgets::{Block, StatefulWidget},
+};
+use rataudio_rta::{Band, DisplayScale, FrequencyAxis, NumberFormat, RTA, RTAState};
+
+const WIDTHS: [u16; 16] = [
+    10, 11, 12, 13, 17, 24, 32, 47, 64, 80, 101, 128, 160, 200, 255, 300,
+];
+const HEIGHTS: [u16; 13] = [3, 4, 5, 6, 7, 8, 10, 13, 17, 24, 32, 45, 60];
+const BAND_COUNTS: [usize; 17] = [
+    1, 2, 3, 5, 8, 10, 16, 31, 32, 61, 64, 100, 128, 255, 256, 511, 512,
+];
+
+/// Bands from 20 Hz to 20 kHz with varying values, the first one at full scale.
+fn bands(count: usize) -> Vec<Band> {
+    (0..count)
+        .map(|i| {
+            let position = i as f32 / (count - 1).max(1) as f32;
+            let value = if i == 0 { 1.0 } else { (i % 10) as f32 / 10.0 };
+            Band::new(value, (20.0 * 1000_f32.powf(position)) as u16)
+        })
+        .collect()
+}
+
+fn symbol(buf: &Buffer, x: u16, y: u16) -> &str {
+    buf[(x, y)].symbol()
+}
+
+fn is_blank(buf: &Buffer, x: u16, y: u16) -> bool {
+    symbol(buf, x, y).trim().is_empty()
+}
+
+/// Renders `rta` into `area` of a larger buffer and checks the invariants of the meter.
+fn check(rta: RTA, area: Rect, inner: Rect, min_db: f32, case: &str) {
+    let mut buf = Buffer::empty(Rect::new(0, 0, area.right() + 3, area.bottom() + 2));
+    let mut state = RTAState::new();
+    rta.render(area, &mut buf, &mut state);
+
+    // Nothing is drawn outside the area.
+    for (i, cell) in buf.content.iter().enumerate() {
+        let (x, y) = buf.pos_of(i);
+        assert!(
+            area.contains(Position::new(x, y)) || *cell == Cell::default(),
+            "{case}: drawn outside the area at ({x}, {y})"
+        );
+    }
+
+    // The meter is only drawn when there is room for it.
+    let corner = inner.positions().find(|p| symbol(&buf, p.x, p.y) == "└");
+    let Some(Position {
+        x: axis_x,
+        y: axis_y,
+    }) = corner
+    else {
+        return;
+    };
+
+    // The x-axis runs right from the corner, and the frequency labels stay below it.
+    let axis_right = (axis_x + 1..inner.right())
+        .take_while(|&x| symbol(&buf, x, axis_y) == "─")
+        .last()
+        .unwrap_or(axis_x);
+    if axis_y + 1 < inner.bottom() {
+        let label_y = axis_y + 1;
+        for x in inner.left()..inner.right() {
+            if !is_blank(&buf, x, label_y) {
+                assert!(
+                    x > axis_x && x <= axis_right,
+                    "{case}: frequency label at column {x} outside the axis {axis_x}..={axis_right}"
+                );
+            }
+        }
+    }
+
+    // The y-axis runs up from the corner, with the bars to its right and the dB labels to its
+    // left, decreasing from top to bottom and within range.
+    let mut previous_db = f32::INFINITY;
+    for y in (inner.top()..axis_y).rev() {
+        if symbol(&buf, axis_x, y) != "│" {
+            break;
+        }
+        for x in axis_right + 1..inner.right() {
+            assert!(
+                is_blank(&buf, x, y),
+                "{case}: drawn right of the bars at ({x}, {y})"
+            );
+        }
+        let label: String = (inner.left()..axis_x).map(|x| symbol(&buf, x, y)).collect();
+        if label.trim().is_empty() {
+            continue;
+        }
+        let db: f32 = label
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{case}: dB label {label:?} is not a number"));
+        assert!(
+            (min_db..=0.0).contains(&db),
+            "{case}: dB label {db} out of range"
+        );
+        // Labels are read bottom-up here, so they must increase.
+        assert!(
+            previous_db == f32::INFINITY || db > previous_db,
+            "{case}: dB labels out of order"
+        );
+        previous_db = db;
+    }
+
+    // The first band is at full scale and sits directly on the axis.
+    if axis_y > inner.top() && symbol(&buf, axis_x, axis_y - 1) == "│" {
+        assert_eq!(
+            symbol(&buf, axis_x + 1, axis_y - 1),
+            "█",
+            "{case}: first bar is not on the axis"
+        );
+    }
+}
+
+#[test]
+fn render_matrix() {
+    for &width in &WIDTHS {
+        for &height in &HEIGHTS {
+            for &count in &BAND_COUNTS {
+                let area = Rect::new(2, 1, width, height);
+                let case = format!("{width}x{height} with {count} bands");
+
+                check(RTA::new(bands(count), -60.0), area, area, -60.0, &case);
+
+                check(
+                    RTA::new(bands(count), -120.0)
+                        .block(Block::bordered())
+                        .show_peak_labels(false)
+                        .piano_keyboard(true)
+                        .frequency_axis(FrequencyAxis::Note),
+                    area,
+                    Block::bordered().inner(area),
+                    -120.0,
+                    &format!("{case}, bordered"),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cached_chrome_matches_fresh_render() {
+    for (width, height) in [(24, 8), (80, 24), (200, 60)] {
+        let area = Rect::new(0, 0, width, height);
+        let mut state = RTAState::new();
+        state.collect_render_stats(true);
+        for frame in 0..3 {
+            let mut cached = Buffer::empty(area);
+            RTA::new(bands(31), -60.0).render(area, &mut cached, &mut state);
+            assert_eq!(
+                state.render_stats().unwrap().chrome_cached,
+                frame > 0,
+                "{width}x{height}"
+            );
+
+            let mut fresh = Buffer::empty(area);
+            RTA::new(bands(31), -60.0).render(area, &mut fresh, &mut RTAState::new());
+            assert_eq!(cached, fresh, "{width}x{height}, frame {frame}");
+        }
+    }
+}
+
+#[test]
+fn chrome_cache_is_invalidated_by_its_inputs() {
+    let area = Rect::new(0, 0, 80, 24);
+    let base = || RTA::new(bands(31), -60.0);
+    let mut shifted_bands = bands(31);
+    for band in &mut shifted_bands {
+        band.frequency = band.frequency.map(|frequency| frequency * 2);
+    }
+    let changes: [(&str, RTA); 4] = [
+        ("min_db", RTA::new(bands(31), -90.0)),
+        ("display_scale", base().display_scale(DisplayScale::Percent)),
+        (
+            "number_format",
+            base().number_format(NumberFormat {
+                kilo_prefix: "K".to_string(),
+                ..Default::default()
+            }),
+        ),
+        ("band frequencies", RTA::new(shifted_bands, -60.0)),
+    ];
+
+    let mut before = Buffer::empty(area);
+    base().render(area, &mut before, &mut RTAState::new());
+    for (input, changed) in changes {
+        let mut state = RTAState::new();
+        state.collect_render_stats(true);
+        let mut buf = Buffer::empty(area);
+        base().render(area, &mut buf, &mut state);
+
+        let mut fresh = Buffer::empty(area);
+        changed
+            .clone()
+            .render(area, &mut fresh, &mut RTAState::new());
+        let mut cached = Buffer::empty(area);
+        changed.render(area, &mut cached, &mut state);
+
+        assert!(!state.render_stats().unwrap().chrome_cached, "{input}");
+        assert_ne!(cached, before, "{input}");
+        assert_eq!(cached, fresh, "{input}");
+    }
+}